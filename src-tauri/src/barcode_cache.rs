@@ -0,0 +1,97 @@
+// src-tauri/src/barcode_cache.rs
+//
+// In-memory LRU of rendered barcodes, so the UI can ask for the same code on
+// every render without re-encoding it.
+
+use crate::barcodes::{CodeKind, DataMatrixShape, ImageFormat};
+use crate::db::Db;
+use crate::error::AppError;
+use lru::LruCache;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use tauri::State;
+
+const CAPACITY_KEY: &str = "barcode_cache_capacity";
+pub const DEFAULT_CAPACITY: NonZeroUsize = NonZeroUsize::new(256).unwrap();
+
+/// Every input that changes the rendered image. Logos are keyed by digest
+/// so a large image isn't held twice.
+#[derive(PartialEq, Eq, Hash)]
+pub struct BarcodeKey {
+    pub kind: CodeKind,
+    pub data: String,
+    pub format: ImageFormat,
+    pub margin: u32,
+    pub logo: Option<[u8; 32]>,
+    pub transparent: bool,
+    pub datamatrix_shape: Option<DataMatrixShape>,
+}
+
+impl BarcodeKey {
+    pub fn logo_digest(logo_png_base64: &str) -> [u8; 32] {
+        Sha256::digest(logo_png_base64.as_bytes()).into()
+    }
+}
+
+/// Data URLs by `BarcodeKey`, managed as Tauri state.
+pub struct BarcodeCache(Mutex<LruCache<BarcodeKey, String>>);
+
+impl BarcodeCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self(Mutex::new(LruCache::new(capacity)))
+    }
+
+    pub fn get(&self, key: &BarcodeKey) -> Option<String> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn put(&self, key: BarcodeKey, data_url: String) {
+        self.0.lock().unwrap().put(key, data_url);
+    }
+}
+
+/// The capacity saved in settings, or the default.
+pub fn capacity(conn: &Connection) -> Result<NonZeroUsize, AppError> {
+    match crate::settings::read_setting(conn, CAPACITY_KEY)? {
+        Some(value) => NonZeroUsize::new(serde_json::from_str(&value)?)
+            .ok_or_else(|| AppError::Validation(format!("{} must be at least 1", CAPACITY_KEY))),
+        None => Ok(DEFAULT_CAPACITY),
+    }
+}
+
+/// Drops every cached image. Returns how many there were.
+#[tauri::command]
+pub fn clear_barcode_cache(cache: State<'_, BarcodeCache>) -> usize {
+    let mut cache = cache.0.lock().unwrap();
+    let count = cache.len();
+    cache.clear();
+    count
+}
+
+#[tauri::command]
+pub async fn get_barcode_cache_capacity(db: State<'_, Db>) -> Result<usize, AppError> {
+    db.run(|conn| Ok(capacity(conn)?.get())).await
+}
+
+/// Saves the capacity and applies it straight away, evicting the least
+/// recently used images if the cache shrinks.
+#[tauri::command]
+pub async fn set_barcode_cache_capacity(
+    db: State<'_, Db>,
+    cache: State<'_, BarcodeCache>,
+    capacity: usize,
+) -> Result<(), AppError> {
+    let capacity = NonZeroUsize::new(capacity)
+        .ok_or_else(|| AppError::Validation("capacity must be at least 1".into()))?;
+
+    db.write(move |conn| {
+        crate::settings::write_setting(conn, CAPACITY_KEY, &capacity.to_string())?;
+        Ok(())
+    })
+    .await?;
+
+    cache.0.lock().unwrap().resize(capacity);
+    Ok(())
+}
@@ -0,0 +1,141 @@
+// src-tauri/src/barcode_grid.rs
+//
+// Tiles several codes into one PNG, e.g. to print a page of distinct codes
+// without going through a PDF.
+
+use crate::barcodes::{encode_png, render_png, CodeKind, WHITE};
+use crate::bitmap_font::{chars_fitting, draw_text, text_width, GLYPH_HEIGHT};
+use base64::prelude::*;
+use image::{imageops, Rgba, RgbaImage};
+use serde::Deserialize;
+
+const DEFAULT_SPACING: u32 = 16;
+// Each font pixel becomes a 2x2 block, so labels are 14px tall
+const LABEL_SCALE: u32 = 2;
+// Largest canvas side, in pixels
+const MAX_CANVAS_SIDE: u32 = 16_384;
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// One cell of the grid: either an already generated PNG (`image`, a data
+/// URL or bare base64) or a code to render (`kind` and `data`).
+#[derive(Deserialize)]
+pub struct GridCell {
+    pub image: Option<String>,
+    pub kind: Option<CodeKind>,
+    pub data: Option<String>,
+    /// Printed centered under the code; ASCII only, cut to the cell width
+    pub label: Option<String>,
+}
+
+fn cell_image(cell: &GridCell) -> Result<RgbaImage, String> {
+    let png = match (&cell.image, &cell.kind, &cell.data) {
+        (Some(image), None, None) => {
+            if image.starts_with("data:") && !image.starts_with("data:image/png;base64,") {
+                return Err("only PNG images are supported".into());
+            }
+            let b64 = image
+                .strip_prefix("data:image/png;base64,")
+                .unwrap_or(image);
+            BASE64_STANDARD
+                .decode(b64.trim())
+                .map_err(|e| format!("invalid base64: {}", e))?
+        }
+        (None, Some(kind), Some(data)) => render_png(kind, data, None)?,
+        _ => return Err("give either `image` or `kind` and `data`".into()),
+    };
+
+    image::load_from_memory(&png)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| format!("invalid image: {}", e))
+}
+
+// Shortens `label` to `max` characters, marking the cut with "..."
+fn fit_label(label: &str, max: usize) -> String {
+    if label.chars().count() <= max {
+        return label.to_string();
+    }
+    let keep = max.saturating_sub(3);
+    let mut out: String = label.chars().take(keep).collect();
+    out.push_str(&"...".chars().take(max - keep).collect::<String>());
+    out
+}
+
+/// Lays the cells out left to right in `columns` columns, `spacing` pixels
+/// apart and from the edges (default 16). Every cell is as large as the
+/// largest code, plus a label row when any cell has a label, and each code
+/// is centered in its cell. Returns a PNG data URL.
+#[tauri::command]
+pub fn compose_barcode_grid(
+    cells: Vec<GridCell>,
+    columns: u32,
+    spacing: Option<u32>,
+) -> Result<String, String> {
+    if cells.is_empty() {
+        return Err("No codes to compose".into());
+    }
+    if columns == 0 {
+        return Err("Columns must be at least 1".into());
+    }
+    let spacing = spacing.unwrap_or(DEFAULT_SPACING);
+
+    let images = cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| cell_image(cell).map_err(|e| format!("Cell {}: {}", i + 1, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let has_labels = cells.iter().any(|c| c.label.is_some());
+    let label_h = if has_labels {
+        GLYPH_HEIGHT * LABEL_SCALE + spacing / 2
+    } else {
+        0
+    };
+
+    let code_w = images.iter().map(|img| img.width()).max().unwrap_or(0);
+    let code_h = images.iter().map(|img| img.height()).max().unwrap_or(0);
+    let cell_h = code_h + label_h;
+
+    let columns = columns.min(images.len() as u32);
+    let rows = (images.len() as u32).div_ceil(columns);
+
+    let side = |count: u32, size: u32| {
+        count
+            .checked_mul(size)
+            .and_then(|s| s.checked_add((count + 1).checked_mul(spacing)?))
+            .filter(|&s| s <= MAX_CANVAS_SIDE)
+    };
+    let (Some(width), Some(height)) = (side(columns, code_w), side(rows, cell_h)) else {
+        return Err(format!(
+            "Grid would be larger than {0}x{0} pixels; use fewer codes, more columns or less spacing",
+            MAX_CANVAS_SIDE
+        ));
+    };
+
+    let mut canvas = RgbaImage::from_pixel(width, height, WHITE);
+    for (i, (img, cell)) in images.iter().zip(&cells).enumerate() {
+        let col = i as u32 % columns;
+        let row = i as u32 / columns;
+        let cell_x = spacing + col * (code_w + spacing);
+        let cell_y = spacing + row * (cell_h + spacing);
+
+        imageops::overlay(
+            &mut canvas,
+            img,
+            (cell_x + (code_w - img.width()) / 2) as i64,
+            (cell_y + (code_h - img.height()) / 2) as i64,
+        );
+
+        if let Some(label) = &cell.label {
+            let label = fit_label(label, chars_fitting(code_w, LABEL_SCALE));
+            let label_x = cell_x + code_w.saturating_sub(text_width(&label, LABEL_SCALE)) / 2;
+            let label_y = cell_y + code_h + spacing / 2;
+            draw_text(&mut canvas, label_x, label_y, &label, LABEL_SCALE, BLACK);
+        }
+    }
+
+    let png = encode_png(&canvas)?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        BASE64_STANDARD.encode(png)
+    ))
+}
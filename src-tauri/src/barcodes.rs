@@ -1,9 +1,17 @@
 // src-tauri/src/barcodes.rs
 
+use crate::barcode_cache::{BarcodeCache, BarcodeKey};
 use base64::prelude::*;
+use datamatrix::data::DataEncodingError;
 use datamatrix::placement::Bitmap;
 use datamatrix::{DataMatrix, SymbolList};
-use quickcodes::{generate, BarcodeType, ExportFormat};
+use image::{imageops, GrayImage, Luma, Rgba, RgbaImage};
+use quickcodes::{
+    export_png, generate, qr, BarcodeConfig, BarcodeModules, BarcodeType, ExportFormat, QRConfig,
+    QRErrorCorrection,
+};
+use std::io::Cursor;
+use tauri::State;
 use urlencoding::encode;
 
 use barcoders::generators::image::{Color, Image as ImageGen, Rotation};
@@ -12,7 +20,7 @@ use barcoders::sym::code128::Code128;
 
 // Types that match what we used on the TS side
 // Types that match what we used on the TS side
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum CodeKind {
     Qr,
@@ -21,7 +29,7 @@ pub enum CodeKind {
     Code128,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum ImageFormat {
     Png,
@@ -33,6 +41,17 @@ fn to_err<E: std::fmt::Debug>(e: E) -> String {
     format!("{:?}", e)
 }
 
+// Weights alternate 1, 3 from the left over the first 12 digits
+fn ean13_check_digit(first12: &str) -> u32 {
+    let sum: u32 = first12
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d } else { 3 * d })
+        .sum();
+    (10 - (sum % 10)) % 10
+}
+
 fn normalize_ean13(input: &str) -> Result<String, String> {
     if !input.chars().all(|c| c.is_ascii_digit()) {
         return Err("EAN-13 must contain digits only".into());
@@ -41,39 +60,12 @@ fn normalize_ean13(input: &str) -> Result<String, String> {
     match input.len() {
         12 => {
             // compute check digit
-            let digits: Vec<u32> = input.chars().map(|c| c.to_digit(10).unwrap()).collect();
-
-            let mut sum_odd = 0; // positions 1,3,5,... (0-based even)
-            let mut sum_even = 0; // positions 2,4,6,... (0-based odd)
-
-            for (i, d) in digits.iter().enumerate() {
-                if i % 2 == 0 {
-                    sum_odd += d;
-                } else {
-                    sum_even += d;
-                }
-            }
-
-            let check = (10 - ((sum_odd + 3 * sum_even) % 10)) % 10;
+            let check = ean13_check_digit(input);
             Ok(format!("{input}{check}"))
         }
         13 => {
-            let digits: Vec<u32> = input.chars().map(|c| c.to_digit(10).unwrap()).collect();
-
-            let check_given = digits[12];
-
-            let mut sum_odd = 0;
-            let mut sum_even = 0;
-
-            for (i, d) in digits[..12].iter().enumerate() {
-                if i % 2 == 0 {
-                    sum_odd += d;
-                } else {
-                    sum_even += d;
-                }
-            }
-
-            let check_calc = (10 - ((sum_odd + 3 * sum_even) % 10)) % 10;
+            let check_given = input[12..].parse::<u32>().unwrap();
+            let check_calc = ean13_check_digit(&input[..12]);
 
             if check_calc != check_given {
                 return Err(format!(
@@ -88,6 +80,56 @@ fn normalize_ean13(input: &str) -> Result<String, String> {
     }
 }
 
+/// The groups printed under an EAN-13 symbol: the leading digit, then the
+/// left and right halves (the right one ends with the check digit).
+#[derive(serde::Serialize)]
+pub struct Ean13Components {
+    pub first_digit: String,
+    pub left: String,
+    pub right: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct Ean13Validation {
+    pub valid: bool,
+    pub normalized: Option<String>,
+    /// Computed from the first 12 digits, even when the input is invalid
+    pub check_digit: Option<u32>,
+    pub components: Option<Ean13Components>,
+    pub error: Option<String>,
+}
+
+/// Validates an EAN-13 without rendering it, for live feedback while typing.
+#[tauri::command]
+pub fn validate_ean13(input: String) -> Ean13Validation {
+    let input = input.trim();
+    let check_digit = input
+        .get(..12)
+        .filter(|first12| first12.chars().all(|c| c.is_ascii_digit()))
+        .map(ean13_check_digit);
+
+    match normalize_ean13(input) {
+        Ok(normalized) => Ean13Validation {
+            valid: true,
+            check_digit,
+            components: Some(Ean13Components {
+                first_digit: normalized[..1].to_string(),
+                left: normalized[1..7].to_string(),
+                right: normalized[7..].to_string(),
+            }),
+            normalized: Some(normalized),
+            error: None,
+        },
+        Err(e) => Ean13Validation {
+            valid: false,
+            normalized: None,
+            check_digit,
+            components: None,
+            error: Some(e),
+        },
+    }
+}
+
 fn clean_for_code128(input: &str) -> String {
     input
         .chars()
@@ -105,54 +147,253 @@ fn to_code128_data(raw: &str) -> String {
     format!("\u{0181}{}", cleaned)
 }
 
-#[tauri::command]
-pub fn generate_barcode(
-    kind: CodeKind,
-    data: String,
-    format: ImageFormat,
+// QR alphanumeric mode: digits, uppercase letters and a few symbols
+fn is_qr_alphanumeric(data: &str) -> bool {
+    data.chars()
+        .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || " $%*+-./:".contains(c))
+}
+
+// Code128 itself has no length limit, so the cap is the width: GS1 allows
+// at most 165 mm including quiet zones, and 0.25 mm is about the narrowest
+// bar handheld scanners read reliably, which leaves 660 modules
+const CODE128_MAX_MODULES: usize = 660;
+const CODE128_QUIET_ZONE: usize = 10;
+
+fn check_code128_width(data: &str) -> Result<(), String> {
+    // Always charset B (see to_code128_data): 11 modules for the start
+    // code, each character and the checksum, 13 for the stop pattern
+    let modules = |chars: usize| 11 * (chars + 2) + 13 + 2 * CODE128_QUIET_ZONE;
+    let chars = clean_for_code128(data).chars().count();
+
+    if modules(chars) > CODE128_MAX_MODULES {
+        let max_chars = (CODE128_MAX_MODULES - modules(0)) / 11;
+        return Err(format!(
+            "Code128 payload too long (max {} chars, got {}): the symbol would be {} modules wide, \
+             more than the {} that fit in 165 mm at a 0.25 mm bar width",
+            max_chars,
+            chars,
+            modules(chars),
+            CODE128_MAX_MODULES
+        ));
+    }
+
+    Ok(())
+}
+
+// Version 40 capacity as (digits, alphanumeric chars, bytes), at
+// quickcodes' default error correction level (M) and at level H
+const QR_CAPACITY_M: (usize, usize, usize) = (5596, 3391, 2331);
+const QR_CAPACITY_H: (usize, usize, usize) = (3057, 1852, 1273);
+
+fn check_qr_payload_length(data: &str, capacity: (usize, usize, usize)) -> Result<(), String> {
+    let (digits, alphanumeric, bytes) = capacity;
+    let (max, unit) = if data.chars().all(|c| c.is_ascii_digit()) {
+        (digits, "digits")
+    } else if is_qr_alphanumeric(data) {
+        (alphanumeric, "chars")
+    } else {
+        (bytes, "bytes")
+    };
+
+    if data.len() > max {
+        return Err(format!(
+            "QR payload too long (max {} {}, got {})",
+            max,
+            unit,
+            data.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Rejects payloads larger than the biggest symbol can hold, with a message
+/// users can act on instead of an encoder error.
+pub(crate) fn check_payload_length(kind: &CodeKind, data: &str) -> Result<(), String> {
+    let all_digits = data.chars().all(|c| c.is_ascii_digit());
+
+    // (name, limit, unit, length in that unit)
+    let (name, max, unit, len) = match kind {
+        CodeKind::Qr => return check_qr_payload_length(data, QR_CAPACITY_M),
+        // Largest square symbol, 144x144
+        CodeKind::Datamatrix if all_digits => ("DataMatrix", 3116, "digits", data.len()),
+        CodeKind::Datamatrix if data.is_ascii() => ("DataMatrix", 2335, "chars", data.len()),
+        CodeKind::Datamatrix => ("DataMatrix", 1555, "bytes", data.len()),
+        // Fixed length; normalize_ean13 reports the exact problem
+        CodeKind::Ean13 => return Ok(()),
+        CodeKind::Code128 => return check_code128_width(data),
+    };
+
+    if len > max {
+        return Err(format!(
+            "{} payload too long (max {} {}, got {})",
+            name, max, unit, len
+        ));
+    }
+
+    Ok(())
+}
+
+/// Symbol shapes a DataMatrix can be restricted to. Without one the encoder
+/// picks the smallest square or rectangle (ISO 16022) that fits.
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DataMatrixShape {
+    /// 10x10 up to 144x144 modules.
+    Square,
+    /// The ISO 16022 rectangles: 8x18, 8x32, 12x26, 12x36, 16x36 and 16x48.
+    /// The largest holds 98 digits or 47 bytes.
+    Rectangle,
+    /// The ISO 16022 rectangles plus the ISO 21471 (DMRE) ones, from 8x48 up
+    /// to 26x64. The largest holds 236 digits or 116 bytes. Older scanners
+    /// may not read DMRE symbols.
+    RectangleExtended,
+}
+
+impl DataMatrixShape {
+    fn symbol_list(self) -> SymbolList {
+        match self {
+            DataMatrixShape::Square => SymbolList::default().enforce_square(),
+            DataMatrixShape::Rectangle => SymbolList::default().enforce_rectangular(),
+            DataMatrixShape::RectangleExtended => {
+                SymbolList::with_extended_rectangles().enforce_rectangular()
+            }
+        }
+    }
+
+    fn largest(self) -> &'static str {
+        match self {
+            DataMatrixShape::Square => "144x144",
+            DataMatrixShape::Rectangle => "16x48",
+            DataMatrixShape::RectangleExtended => "26x64",
+        }
+    }
+}
+
+// The length pre-check is an upper bound; mixed content can still overflow
+fn encode_datamatrix(data: &str, shape: Option<DataMatrixShape>) -> Result<DataMatrix, String> {
+    let symbols = shape.map(DataMatrixShape::symbol_list).unwrap_or_default();
+
+    DataMatrix::encode(data.as_bytes(), symbols).map_err(|e| match (e, shape) {
+        // With a constrained list the encoder reports SymbolListEmpty when no
+        // size left is large enough
+        (
+            DataEncodingError::TooMuchOrIllegalData | DataEncodingError::SymbolListEmpty,
+            Some(shape),
+        ) => format!(
+            "DataMatrix payload does not fit the requested shape (largest {})",
+            shape.largest()
+        ),
+        (DataEncodingError::TooMuchOrIllegalData, None) => {
+            "DataMatrix payload too long for the largest symbol".to_string()
+        }
+        (e, _) => format!("encode error: {:?}", e),
+    })
+}
+
+/// Renders a code as raw PNG bytes. Shared by `generate_barcode` and the
+/// label sheet so both produce identical images. `shape` only applies to
+/// DataMatrix.
+pub fn render_png(
+    kind: &CodeKind,
+    data: &str,
+    shape: Option<DataMatrixShape>,
+) -> Result<Vec<u8>, String> {
+    check_payload_length(kind, data)?;
+
+    match kind {
+        CodeKind::Qr => generate(BarcodeType::QRCode, data, ExportFormat::PNG).map_err(to_err),
+        CodeKind::Ean13 => {
+            let normalized = normalize_ean13(data)?;
+            generate(BarcodeType::EAN13, &normalized, ExportFormat::PNG).map_err(to_err)
+        }
+        CodeKind::Code128 => {
+            let cleaned = to_code128_data(data);
+            if cleaned.is_empty() {
+                return Err("Input is empty or only contains invalid characters".into());
+            }
+
+            let barcode = Code128::new(&cleaned).map_err(|e| format!("Code128 error: {:?}", e))?;
+            let encoded = barcode.encode();
+
+            let img_gen = ImageGen::PNG {
+                height: 80,
+                xdim: 2, // bar width in px; 2–3 px is safe for scanners
+                rotation: Rotation::Zero,
+                background: Color::new([255, 255, 255, 255]), // white
+                foreground: Color::new([0, 0, 0, 255]),       // black
+            };
+
+            img_gen
+                .generate(&encoded)
+                .map_err(|e| format!("Code128 PNG error: {:?}", e))
+        }
+        CodeKind::Datamatrix => {
+            let code = encode_datamatrix(data, shape)?;
+
+            let bitmap: Bitmap<bool> = code.bitmap();
+
+            // Same geometry as the SVG branch
+            let module_size: u32 = 10;
+            let quiet_zone: u32 = 1;
+
+            let total_w = (bitmap.width() as u32 + 2 * quiet_zone) * module_size;
+            let total_h = (bitmap.height() as u32 + 2 * quiet_zone) * module_size;
+
+            let mut img = GrayImage::from_pixel(total_w, total_h, Luma([255]));
+            for (x, y) in bitmap.pixels() {
+                let x_px = (x as u32 + quiet_zone) * module_size;
+                let y_px = (y as u32 + quiet_zone) * module_size;
+                for dy in 0..module_size {
+                    for dx in 0..module_size {
+                        img.put_pixel(x_px + dx, y_px + dy, Luma([0]));
+                    }
+                }
+            }
+
+            let mut png_bytes = Vec::new();
+            img.write_to(&mut Cursor::new(&mut png_bytes), image::ImageFormat::Png)
+                .map_err(|e| format!("DataMatrix PNG error: {:?}", e))?;
+            Ok(png_bytes)
+        }
+    }
+}
+
+/// Renders a code as an SVG document. `shape` only applies to DataMatrix.
+pub fn render_svg(
+    kind: &CodeKind,
+    data: &str,
+    shape: Option<DataMatrixShape>,
 ) -> Result<String, String> {
-    match (kind, format) {
+    check_payload_length(kind, data)?;
+
+    match kind {
         //
         // ---------- QR via quickcodes ----------
         //
-        (CodeKind::Qr, ImageFormat::Svg) => {
+        CodeKind::Qr => {
             let svg_bytes =
-                generate(BarcodeType::QRCode, &data, ExportFormat::SVG).map_err(to_err)?;
-            let svg = String::from_utf8(svg_bytes).map_err(to_err)?;
-            Ok(format!("data:image/svg+xml;utf8,{}", encode(&svg)))
-        }
-        (CodeKind::Qr, ImageFormat::Png) => {
-            let png_bytes =
-                generate(BarcodeType::QRCode, &data, ExportFormat::PNG).map_err(to_err)?;
-            let b64 = BASE64_STANDARD.encode(png_bytes);
-            Ok(format!("data:image/png;base64,{}", b64))
+                generate(BarcodeType::QRCode, data, ExportFormat::SVG).map_err(to_err)?;
+            String::from_utf8(svg_bytes).map_err(to_err)
         }
 
         //
         // ---------- EAN-13 via quickcodes ----------
         //
-        (CodeKind::Ean13, ImageFormat::Svg) => {
-            let normalized = normalize_ean13(&data)?;
+        CodeKind::Ean13 => {
+            let normalized = normalize_ean13(data)?;
             let svg_bytes =
                 generate(BarcodeType::EAN13, &normalized, ExportFormat::SVG).map_err(to_err)?;
-            let svg = String::from_utf8(svg_bytes).map_err(to_err)?;
-            Ok(format!("data:image/svg+xml;utf8,{}", encode(&svg)))
-        }
-        (CodeKind::Ean13, ImageFormat::Png) => {
-            let normalized = normalize_ean13(&data)?;
-            let png_bytes =
-                generate(BarcodeType::EAN13, &normalized, ExportFormat::PNG).map_err(to_err)?;
-            let b64 = BASE64_STANDARD.encode(png_bytes);
-            Ok(format!("data:image/png;base64,{}", b64))
+            String::from_utf8(svg_bytes).map_err(to_err)
         }
 
         //
         // ---------- Code128 via barcoders ----------
         //
-        (CodeKind::Code128, ImageFormat::Svg) => {
+        CodeKind::Code128 => {
             eprintln!("Entering Code128 SVG branch with data: {data:?}");
 
-            let cleaned = to_code128_data(&data);
+            let cleaned = to_code128_data(data);
             if cleaned.is_empty() {
                 return Err("Input is empty or only contains invalid characters".into());
             }
@@ -163,45 +404,17 @@ pub fn generate_barcode(
             // height in px; 60–100 is usually good
             let svg_gen = SvgGen::new(80);
             // SvgGen::generate already returns Result<String, Error>
-            let svg_str = svg_gen
+            svg_gen
                 .generate(&encoded)
-                .map_err(|e| format!("Code128 SVG error: {:?}", e))?;
-
-            Ok(format!("data:image/svg+xml;utf8,{}", encode(&svg_str)))
-        }
-
-        (CodeKind::Code128, ImageFormat::Png) => {
-            let cleaned = to_code128_data(&data);
-            if cleaned.is_empty() {
-                return Err("Input is empty or only contains invalid characters".into());
-            }
-
-            let barcode = Code128::new(&cleaned).map_err(|e| format!("Code128 error: {:?}", e))?;
-            let encoded = barcode.encode();
-
-            let img_gen = ImageGen::PNG {
-                height: 80,
-                xdim: 2, // bar width in px; 2–3 px is safe for scanners
-                rotation: Rotation::Zero,
-                background: Color::new([255, 255, 255, 255]), // white
-                foreground: Color::new([0, 0, 0, 255]),       // black
-            };
-
-            let png_bytes = img_gen
-                .generate(&encoded)
-                .map_err(|e| format!("Code128 PNG error: {:?}", e))?;
-
-            let b64 = BASE64_STANDARD.encode(png_bytes);
-            Ok(format!("data:image/png;base64,{}", b64))
+                .map_err(|e| format!("Code128 SVG error: {:?}", e))
         }
 
         //
         // ---------- DataMatrix via datamatrix crate ----------
         //
-        (CodeKind::Datamatrix, ImageFormat::Svg) => {
+        CodeKind::Datamatrix => {
             // Encode the payload
-            let code = DataMatrix::encode(data.as_bytes(), SymbolList::default())
-                .map_err(|e| format!("encode error: {:?}", e))?;
+            let code = encode_datamatrix(data, shape)?;
 
             let bitmap: Bitmap<bool> = code.bitmap();
 
@@ -247,14 +460,360 @@ pub fn generate_barcode(
 
             svg.push_str("</svg>");
 
-            // Pack as data URL for <img src="...">
-            let encoded = encode(&svg);
-            Ok(format!("data:image/svg+xml;utf8,{}", encoded))
+            Ok(svg)
         }
+    }
+}
+
+// Parses the leading number of an attribute value ("104", "57.5px")
+fn parse_length(value: &str) -> Option<f64> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+// Returns the value of `name="..."` inside an element's opening tag
+fn attr_value<'a>(tag: &'a str, name: &str) -> Option<(usize, &'a str)> {
+    let needle = format!(" {}=\"", name);
+    let start = tag.find(&needle)? + needle.len();
+    let len = tag[start..].find('"')?;
+    Some((start, &tag[start..start + len]))
+}
+
+fn set_attr(tag: &mut String, name: &str, value: &str) {
+    if let Some((start, old)) = attr_value(tag, name) {
+        let end = start + old.len();
+        tag.replace_range(start..end, value);
+    }
+}
+
+/// Grows the SVG canvas by `margin` units on every side: the viewBox is
+/// widened around the existing content, numeric `width`/`height` attributes
+/// are enlarged to match, and (unless `transparent`) a background rect
+/// covers the new area.
+fn pad_svg(svg: &str, margin: u32, transparent: bool) -> Result<String, String> {
+    if margin == 0 {
+        return Ok(svg.to_string());
+    }
+
+    let open_start = svg.find("<svg").ok_or("SVG has no <svg> element")?;
+    let open_end = open_start + svg[open_start..].find('>').ok_or("Malformed <svg> tag")?;
+    let mut tag = svg[open_start..open_end].to_string();
+
+    let (_, view_box) = attr_value(&tag, "viewBox").ok_or("SVG has no viewBox")?;
+    let nums: Vec<f64> = view_box
+        .split([' ', ','])
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect();
+    let [x, y, w, h] = nums[..] else {
+        return Err(format!("Unsupported SVG viewBox '{}'", view_box));
+    };
+
+    let m = margin as f64;
+    let (new_w, new_h) = (w + 2.0 * m, h + 2.0 * m);
+    set_attr(
+        &mut tag,
+        "viewBox",
+        &format!("{} {} {} {}", x - m, y - m, new_w, new_h),
+    );
+    // Percentage sizes already follow the viewport, so only numbers change
+    for (name, extent) in [("width", w), ("height", h)] {
+        let size = attr_value(&tag, name)
+            .filter(|(_, v)| !v.ends_with('%'))
+            .and_then(|(_, v)| parse_length(v));
+        if let Some(size) = size {
+            // Keep the size to viewBox ratio so the content is not rescaled
+            let grown = size + 2.0 * m * size / extent;
+            set_attr(
+                &mut tag,
+                name,
+                &((grown * 1000.0).round() / 1000.0).to_string(),
+            );
+        }
+    }
+
+    let background = if transparent {
+        String::new()
+    } else {
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="white"/>"#,
+            x - m,
+            y - m,
+            new_w,
+            new_h
+        )
+    };
+
+    Ok(format!(
+        "{}{}>{}{}",
+        &svg[..open_start],
+        tag,
+        background,
+        &svg[open_end + 1..]
+    ))
+}
+
+pub(crate) fn encode_png(img: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png_bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut png_bytes), image::ImageFormat::Png)
+        .map_err(to_err)?;
+    Ok(png_bytes)
+}
+
+pub(crate) const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
 
-        (CodeKind::Datamatrix, ImageFormat::Png) => {
-            // If you need PNG DataMatrix, we can rasterize via `image` crate later.
-            Err("PNG for DataMatrix not implemented yet – use SVG".to_string())
+// Every generator draws dark modules on pure white, so white is background
+fn clear_background(img: &mut RgbaImage) {
+    for pixel in img.pixels_mut() {
+        if *pixel == WHITE {
+            pixel.0[3] = 0;
         }
     }
 }
+
+fn make_png_transparent(png: &[u8]) -> Result<Vec<u8>, String> {
+    let mut img = image::load_from_memory(png).map_err(to_err)?.to_rgba8();
+    clear_background(&mut img);
+    encode_png(&img)
+}
+
+// Drops the full-size white rect each generator emits first inside <svg>
+fn strip_svg_background(svg: &str) -> String {
+    let Some(open_end) = svg
+        .find("<svg")
+        .and_then(|start| svg[start..].find('>').map(|i| start + i + 1))
+    else {
+        return svg.to_string();
+    };
+
+    let rest = svg[open_end..].trim_start();
+    let Some(rect_len) = rest.strip_prefix("<rect").and_then(|r| r.find("/>")) else {
+        return svg.to_string();
+    };
+    let rect = &rest[.."<rect".len() + rect_len + 2];
+    if !(rect.contains(r#"fill="white""#) || rect.contains(r##"fill="#ffffff""##)) {
+        return svg.to_string();
+    }
+
+    let rect_start = svg.len() - rest.len();
+    format!("{}{}", &svg[..rect_start], &svg[rect_start + rect.len()..])
+}
+
+/// Adds `margin` pixels on every side, filled with white (or fully
+/// transparent when `transparent` is set).
+fn pad_png(png: &[u8], margin: u32, transparent: bool) -> Result<Vec<u8>, String> {
+    if margin == 0 {
+        return Ok(png.to_vec());
+    }
+
+    let img = image::load_from_memory(png).map_err(to_err)?.to_rgba8();
+    // Not the corner pixel: Code128 PNGs start with a bar, not quiet zone
+    let mut background = WHITE;
+    if transparent {
+        background.0[3] = 0;
+    }
+
+    let mut canvas = RgbaImage::from_pixel(
+        img.width() + 2 * margin,
+        img.height() + 2 * margin,
+        background,
+    );
+    imageops::replace(&mut canvas, &img, margin as i64, margin as i64);
+
+    encode_png(&canvas)
+}
+
+// The logo is scaled to fit a square this fraction of the symbol's width.
+// With its border it hides at most 12% of the symbol (on the smallest,
+// 21-module version), well within the ~30% level H can recover.
+const LOGO_WIDTH_FRACTION: f32 = 0.25;
+
+/// QR PNG at error correction level H with `logo_b64` (a PNG, optionally as
+/// a data URL) composited in the middle on a white border one module wide.
+fn render_qr_with_logo(data: &str, logo_b64: &str, transparent: bool) -> Result<Vec<u8>, String> {
+    check_qr_payload_length(data, QR_CAPACITY_H)
+        .map_err(|e| format!("{} with a logo, which needs error correction level H", e))?;
+
+    let config = BarcodeConfig {
+        qr_config: QRConfig {
+            error_correction: QRErrorCorrection::High,
+            version: None,
+        },
+        ..Default::default()
+    };
+    let barcode = qr::generate_qr_with_config(data, &config).map_err(to_err)?;
+    let modules = match &barcode.modules {
+        BarcodeModules::Matrix(matrix) => matrix.len() as u32,
+        BarcodeModules::Linear(_) => return Err("QR generator returned a 1D code".into()),
+    };
+    let png = export_png(&barcode).map_err(to_err)?;
+
+    let logo_b64 = logo_b64
+        .strip_prefix("data:image/png;base64,")
+        .unwrap_or(logo_b64);
+    let logo_bytes = BASE64_STANDARD
+        .decode(logo_b64.trim())
+        .map_err(|e| format!("Invalid logo base64: {}", e))?;
+    let logo =
+        image::load_from_memory(&logo_bytes).map_err(|e| format!("Invalid logo image: {}", e))?;
+
+    let mut canvas = image::load_from_memory(&png).map_err(to_err)?.to_rgba8();
+    // Before the logo goes on, so its white border stays opaque
+    if transparent {
+        clear_background(&mut canvas);
+    }
+    let quiet = barcode.config.margin;
+    let symbol_px = canvas.width() - 2 * quiet;
+    let module_px = symbol_px / modules;
+
+    let max_logo = ((symbol_px as f32 * LOGO_WIDTH_FRACTION) as u32).max(1);
+    let logo = logo.resize(max_logo, max_logo, imageops::FilterType::Lanczos3);
+
+    let box_w = logo.width() + 2 * module_px;
+    let box_h = logo.height() + 2 * module_px;
+    let box_x = (canvas.width() - box_w) / 2;
+    let box_y = (canvas.height() - box_h) / 2;
+    for y in box_y..box_y + box_h {
+        for x in box_x..box_x + box_w {
+            canvas.put_pixel(x, y, WHITE);
+        }
+    }
+    imageops::overlay(
+        &mut canvas,
+        &logo.to_rgba8(),
+        (box_x + module_px) as i64,
+        (box_y + module_px) as i64,
+    );
+
+    encode_png(&canvas)
+}
+
+/// `margin` (pixels, default 0) pads the finished image on all sides and is
+/// included in its reported width and height. `logo_png_base64` overlays a
+/// logo on QR codes (PNG output only). `transparent` drops the white
+/// background without changing the image size. `datamatrix_shape` restricts
+/// DataMatrix codes to square or rectangular symbols (see `DataMatrixShape`).
+/// Results are cached by every one of these inputs.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_barcode(
+    cache: State<'_, BarcodeCache>,
+    kind: CodeKind,
+    data: String,
+    format: ImageFormat,
+    margin: Option<u32>,
+    logo_png_base64: Option<String>,
+    transparent: Option<bool>,
+    datamatrix_shape: Option<DataMatrixShape>,
+) -> Result<String, String> {
+    let key = BarcodeKey {
+        kind,
+        data,
+        format,
+        margin: margin.unwrap_or(0),
+        logo: logo_png_base64.as_deref().map(BarcodeKey::logo_digest),
+        transparent: transparent.unwrap_or(false),
+        datamatrix_shape,
+    };
+    if let Some(data_url) = cache.get(&key) {
+        return Ok(data_url);
+    }
+
+    let data_url = render_data_url(
+        kind,
+        key.data.clone(),
+        format,
+        margin,
+        logo_png_base64,
+        transparent,
+        datamatrix_shape,
+    )?;
+    cache.put(key, data_url.clone());
+    Ok(data_url)
+}
+
+/// `generate_barcode` without the cache, for callers that build one-off
+/// payloads.
+pub fn render_data_url(
+    kind: CodeKind,
+    data: String,
+    format: ImageFormat,
+    margin: Option<u32>,
+    logo_png_base64: Option<String>,
+    transparent: Option<bool>,
+    datamatrix_shape: Option<DataMatrixShape>,
+) -> Result<String, String> {
+    let margin = margin.unwrap_or(0);
+    let transparent = transparent.unwrap_or(false);
+
+    if datamatrix_shape.is_some() && !matches!(kind, CodeKind::Datamatrix) {
+        return Err("A symbol shape is only supported for DataMatrix codes".into());
+    }
+
+    if let Some(logo) = logo_png_base64 {
+        if !matches!((&kind, &format), (CodeKind::Qr, ImageFormat::Png)) {
+            return Err("A logo is only supported for QR codes in PNG format".into());
+        }
+        let png_bytes = pad_png(
+            &render_qr_with_logo(&data, &logo, transparent)?,
+            margin,
+            transparent,
+        )?;
+        let b64 = BASE64_STANDARD.encode(png_bytes);
+        return Ok(format!("data:image/png;base64,{}", b64));
+    }
+
+    match format {
+        ImageFormat::Svg => {
+            let mut svg = render_svg(&kind, &data, datamatrix_shape)?;
+            if transparent {
+                svg = strip_svg_background(&svg);
+            }
+            let svg = pad_svg(&svg, margin, transparent)?;
+            // Pack as data URL for <img src="...">
+            Ok(format!("data:image/svg+xml;utf8,{}", encode(&svg)))
+        }
+        ImageFormat::Png => {
+            let mut png_bytes = render_png(&kind, &data, datamatrix_shape)?;
+            if transparent {
+                png_bytes = make_png_transparent(&png_bytes)?;
+            }
+            let png_bytes = pad_png(&png_bytes, margin, transparent)?;
+            let b64 = BASE64_STANDARD.encode(png_bytes);
+            Ok(format!("data:image/png;base64,{}", b64))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logo_qr_uses_level_h_capacity() {
+        let tiny_logo = {
+            let img = RgbaImage::from_pixel(4, 4, WHITE);
+            BASE64_STANDARD.encode(encode_png(&img).unwrap())
+        };
+
+        let data = "a".repeat(1274);
+        assert!(check_payload_length(&CodeKind::Qr, &data).is_ok());
+        let err = render_qr_with_logo(&data, &tiny_logo, false).unwrap_err();
+        assert!(err.contains("max 1273 bytes"), "{}", err);
+
+        assert!(render_qr_with_logo(&"a".repeat(1273), &tiny_logo, false).is_ok());
+        let err = render_qr_with_logo(&"1".repeat(3058), &tiny_logo, false).unwrap_err();
+        assert!(err.contains("max 3057 digits"), "{}", err);
+    }
+
+    #[test]
+    fn code128_limit_follows_symbol_width() {
+        assert!(check_payload_length(&CodeKind::Code128, &"A".repeat(55)).is_ok());
+        let err = check_payload_length(&CodeKind::Code128, &"A".repeat(56)).unwrap_err();
+        assert!(err.contains("max 55 chars"), "{}", err);
+        // Dropped control characters don't count
+        assert!(check_payload_length(&CodeKind::Code128, &"A\n".repeat(55)).is_ok());
+    }
+}
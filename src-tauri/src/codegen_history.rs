@@ -1,6 +1,19 @@
+use crate::db::{normalize_timestamp, Db};
+use crate::error::AppError;
+use crate::export_format::ExportEnvelope;
+use crate::tools::diff::unified_diff;
 use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::types::{Type, Value};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use tauri::{Emitter, State};
 
 #[derive(Serialize, Deserialize)]
 pub struct CodegenHistoryEntry {
@@ -9,120 +22,713 @@ pub struct CodegenHistoryEntry {
     pub summary: String,
     pub payload: String,
     pub created_at: String,
+    // Missing in files exported before pinning existed
+    #[serde(default)]
+    pub is_pinned: bool,
 }
 
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CodegenMode {
+    Single,
+    Multi,
+}
+
+impl CodegenMode {
+    const ALL: [CodegenMode; 2] = [CodegenMode::Single, CodegenMode::Multi];
+
+    /// The canonical form stored in the `mode` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CodegenMode::Single => "single",
+            CodegenMode::Multi => "multi",
+        }
+    }
+
+    // For modes that arrive as plain strings, e.g. inside an import file
+    fn parse(mode: &str) -> Result<Self, AppError> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str() == mode)
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Unknown codegen mode '{}', expected one of: {}",
+                    mode,
+                    Self::ALL.map(|m| m.as_str()).join(", ")
+                ))
+            })
+    }
+}
+
+pub const CODEGEN_UPDATED_EVENT: &str = "codegen-updated";
+
+// Payload of `codegen-updated`, e.g. `{ "kind": "deleted", "id": 3 }`
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum CodegenChange {
+    Saved { id: i64 },
+    Updated { id: i64 },
+    Deleted { id: i64 },
+    Pinned { id: i64, pinned: bool },
+    Imported { count: usize },
+    Pruned { count: usize },
+    Cleared { count: usize },
+    Reset,
+}
+
+pub(crate) fn emit_codegen_updated(app: &tauri::AppHandle, change: CodegenChange) {
+    if let Err(e) = app.emit(CODEGEN_UPDATED_EVENT, change) {
+        eprintln!("Failed to emit {CODEGEN_UPDATED_EVENT}: {e}");
+    }
+}
+
+// Payloads above this size are gzipped before being stored
+const COMPRESSION_THRESHOLD: usize = 1024;
+
+const ENTRY_COLUMNS: &str = "id, mode, summary, payload, compressed, created_at, is_pinned";
+
+// Returns the value to store in the `payload` column plus the `compressed` flag
+fn encode_payload(payload: &str) -> Result<(Value, bool), AppError> {
+    if payload.len() <= COMPRESSION_THRESHOLD {
+        return Ok((Value::Text(payload.to_string()), false));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(payload.as_bytes())
+        .map_err(|e| AppError::Io(format!("Failed to compress payload: {}", e)))?;
+    let bytes = encoder
+        .finish()
+        .map_err(|e| AppError::Io(format!("Failed to compress payload: {}", e)))?;
+
+    Ok((Value::Blob(bytes), true))
+}
+
+fn decode_payload(
+    raw: Value,
+    compressed: bool,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    match raw {
+        // Legacy rows (and small payloads) are stored as plain text
+        Value::Text(text) if !compressed => Ok(text),
+        Value::Blob(bytes) if compressed => {
+            let mut payload = String::new();
+            GzDecoder::new(bytes.as_slice()).read_to_string(&mut payload)?;
+            Ok(payload)
+        }
+        _ => Err("Unexpected payload storage format".into()),
+    }
+}
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<CodegenHistoryEntry> {
+    let raw: Value = row.get(3)?;
+    let compressed: bool = row.get(4)?;
+    let payload = decode_payload(raw, compressed)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, Type::Blob, e))?;
+
+    Ok(CodegenHistoryEntry {
+        id: row.get(0)?,
+        mode: row.get(1)?,
+        summary: row.get(2)?,
+        payload,
+        created_at: row.get(5)?,
+        is_pinned: row.get(6)?,
+    })
+}
+
+pub(crate) const MAX_HISTORY_KEY: &str = "max_history";
+const DEFAULT_MAX_HISTORY: i64 = 500;
+
+// A stored value below 1 would prune everything (0) or nothing (SQLite
+// reads a negative LIMIT as no limit), so anything but an integer of at
+// least 1 falls back to the default.
+fn max_history(conn: &Connection) -> Result<i64, AppError> {
+    let stored = crate::settings::read_setting(conn, MAX_HISTORY_KEY)?
+        .and_then(|value| serde_json::from_str::<i64>(&value).ok())
+        .filter(|&n| n >= 1);
+    Ok(stored.unwrap_or(DEFAULT_MAX_HISTORY))
+}
+
+// Only checks the payload parses; the original string is what gets stored
+fn validate_payload(payload: &str) -> Result<(), AppError> {
+    serde_json::from_str::<serde_json::Value>(payload)
+        .map(|_| ())
+        .map_err(|e| AppError::Validation(format!("Invalid payload JSON: {}", e)))
+}
+
+fn insert_entry(
+    conn: &Connection,
+    mode: &str,
+    summary: &str,
+    payload: &str,
+    created_at: &str,
+    is_pinned: bool,
+) -> Result<i64, AppError> {
+    let (stored, compressed) = encode_payload(payload)?;
+    conn.execute(
+        "INSERT INTO codegen_history (mode, summary, payload, compressed, created_at, is_pinned)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        (
+            mode,
+            summary,
+            stored,
+            compressed,
+            normalize_timestamp(created_at),
+            is_pinned,
+        ),
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// Pinned entries neither count towards `max_history` nor get deleted
+fn prune_history(conn: &Connection) -> Result<usize, AppError> {
+    let limit = max_history(conn)?;
+    let pruned = conn.execute(
+        "DELETE FROM codegen_history
+         WHERE is_pinned = 0 AND id NOT IN (
+             SELECT id FROM codegen_history
+             WHERE is_pinned = 0
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?1
+         )",
+        (limit,),
+    )?;
+    Ok(pruned)
+}
+
+fn mode_filter(mode: Option<&str>) -> &'static str {
+    if mode.is_some() {
+        " WHERE mode = ?1"
+    } else {
+        ""
+    }
+}
+
+// `offset` without `limit` skips rows and returns the rest
+fn query_history(
+    conn: &Connection,
+    mode: Option<&str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<CodegenHistoryEntry>, AppError> {
+    let mut sql = format!(
+        "SELECT {} FROM codegen_history{} ORDER BY is_pinned DESC, created_at DESC, id DESC",
+        ENTRY_COLUMNS,
+        mode_filter(mode)
+    );
+    if limit.is_some() || offset.is_some() {
+        sql.push_str(&format!(
+            " LIMIT {} OFFSET {}",
+            limit.unwrap_or(-1),
+            offset.unwrap_or(0)
+        ));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt.query_map(rusqlite::params_from_iter(mode.iter()), row_to_entry)?;
+
+    let mut entries = Vec::new();
+    for r in rows {
+        entries.push(r?);
+    }
+
+    Ok(entries)
+}
+
+fn count_history(conn: &Connection, mode: Option<&str>) -> Result<i64, AppError> {
+    let count = conn.query_row(
+        &format!("SELECT COUNT(*) FROM codegen_history{}", mode_filter(mode)),
+        rusqlite::params_from_iter(mode.iter()),
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+fn query_entry(conn: &Connection, id: i64) -> Result<Option<CodegenHistoryEntry>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            "SELECT {} FROM codegen_history WHERE id = ?1",
+            ENTRY_COLUMNS
+        ),
+        (id,),
+        row_to_entry,
+    );
+
+    match result {
+        Ok(entry) => Ok(Some(entry)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Saves a snapshot and prunes the oldest rows beyond `max_history`.
+/// Returns how many rows were pruned.
 #[tauri::command]
-pub fn save_codegen_state(
+pub async fn save_codegen_state(
     app: tauri::AppHandle,
-    mode: String,
+    db: State<'_, Db>,
+    mode: CodegenMode,
     summary: String,
     payload: String,
-) -> Result<(), String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
+) -> Result<usize, AppError> {
+    validate_payload(&payload)?;
+
     let now = Utc::now().to_rfc3339();
 
-    conn.execute(
-        "INSERT INTO codegen_history (mode, summary, payload, created_at)
-         VALUES (?1, ?2, ?3, ?4)",
-        (mode, summary, payload, now),
-    )
-    .map_err(|e| e.to_string())?;
+    let (id, pruned) = db
+        .write(move |conn| {
+            let tx = conn.transaction()?;
+
+            let id = insert_entry(&tx, mode.as_str(), &summary, &payload, &now, false)?;
+            let pruned = prune_history(&tx)?;
+
+            tx.commit()?;
+
+            Ok((id, pruned))
+        })
+        .await?;
 
+    emit_codegen_updated(&app, CodegenChange::Saved { id });
+    if pruned > 0 {
+        emit_codegen_updated(&app, CodegenChange::Pruned { count: pruned });
+    }
+
+    Ok(pruned)
+}
+
+#[tauri::command]
+pub async fn get_codegen_max_history(db: State<'_, Db>) -> Result<i64, AppError> {
+    db.run(|conn| max_history(conn)).await
+}
+
+#[tauri::command]
+pub async fn set_codegen_max_history(db: State<'_, Db>, max_history: i64) -> Result<(), AppError> {
+    if max_history < 1 {
+        return Err(AppError::Validation(
+            "max_history must be at least 1".into(),
+        ));
+    }
+
+    db.write(move |conn| {
+        crate::settings::write_setting(conn, MAX_HISTORY_KEY, &max_history.to_string())?;
+        Ok(())
+    })
+    .await
+}
+
+fn validate_page(limit: Option<i64>, offset: Option<i64>) -> Result<(), AppError> {
+    if limit.is_some_and(|l| l < 1) {
+        return Err(AppError::Validation("limit must be at least 1".into()));
+    }
+    if offset.is_some_and(|o| o < 0) {
+        return Err(AppError::Validation("offset must not be negative".into()));
+    }
     Ok(())
 }
 
+/// Pinned entries first, then newest first. Without `limit`/`offset` every
+/// matching entry is returned.
 #[tauri::command]
-pub fn get_codegen_history(app: tauri::AppHandle) -> Result<Vec<CodegenHistoryEntry>, String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
+pub async fn get_codegen_history(
+    db: State<'_, Db>,
+    mode: Option<CodegenMode>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<CodegenHistoryEntry>, AppError> {
+    validate_page(limit, offset)?;
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, mode, summary, payload, created_at
-             FROM codegen_history
-             ORDER BY created_at DESC",
-        )
-        .map_err(|e| e.to_string())?;
+    db.run(move |conn| query_history(conn, mode.map(CodegenMode::as_str), limit, offset))
+        .await
+}
 
-    let rows = stmt
-        .query_map([], |row| {
-            Ok(CodegenHistoryEntry {
-                id: row.get(0)?,
-                mode: row.get(1)?,
-                summary: row.get(2)?,
-                payload: row.get(3)?,
-                created_at: row.get(4)?,
-            })
+#[tauri::command]
+pub async fn count_codegen_history(
+    db: State<'_, Db>,
+    mode: Option<CodegenMode>,
+) -> Result<i64, AppError> {
+    db.run(move |conn| count_history(conn, mode.map(CodegenMode::as_str)))
+        .await
+}
+
+/// Newest entry for `mode` regardless of pinning, or `None` if there is none.
+#[tauri::command]
+pub async fn get_latest_codegen(
+    db: State<'_, Db>,
+    mode: CodegenMode,
+) -> Result<Option<CodegenHistoryEntry>, AppError> {
+    db.run(move |conn| {
+        let result = conn.query_row(
+            &format!(
+                "SELECT {} FROM codegen_history WHERE mode = ?1
+                 ORDER BY created_at DESC, id DESC LIMIT 1",
+                ENTRY_COLUMNS
+            ),
+            (mode.as_str(),),
+            row_to_entry,
+        );
+
+        match result {
+            Ok(entry) => Ok(Some(entry)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_codegen_entry(
+    db: State<'_, Db>,
+    id: i64,
+) -> Result<Option<CodegenHistoryEntry>, AppError> {
+    db.run(move |conn| query_entry(conn, id)).await
+}
+
+fn pretty_payload(payload: &str) -> Result<String, AppError> {
+    let value: serde_json::Value = serde_json::from_str(payload)
+        .map_err(|e| AppError::Validation(format!("Invalid payload JSON: {}", e)))?;
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Unified diff of two entries' payloads, line-based on pretty-printed JSON.
+#[tauri::command]
+pub async fn diff_codegen_entries(
+    db: State<'_, Db>,
+    id_a: i64,
+    id_b: i64,
+) -> Result<String, AppError> {
+    let (entry_a, entry_b) = db
+        .run(move |conn| Ok((query_entry(conn, id_a)?, query_entry(conn, id_b)?)))
+        .await?;
+
+    let entry_a = entry_a
+        .ok_or_else(|| AppError::NotFound(format!("Codegen history entry {} not found", id_a)))?;
+    let entry_b = entry_b
+        .ok_or_else(|| AppError::NotFound(format!("Codegen history entry {} not found", id_b)))?;
+
+    let pretty_a = pretty_payload(&entry_a.payload)?;
+    let pretty_b = pretty_payload(&entry_b.payload)?;
+
+    Ok(unified_diff(
+        &pretty_a,
+        &pretty_b,
+        &format!("entry {}", id_a),
+        &format!("entry {}", id_b),
+        3,
+    ))
+}
+
+#[tauri::command]
+pub async fn delete_codegen_entry(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+) -> Result<(), AppError> {
+    let deleted = db
+        .write(move |conn| Ok(conn.execute("DELETE FROM codegen_history WHERE id = ?1", (id,))?))
+        .await?;
+
+    if deleted > 0 {
+        emit_codegen_updated(&app, CodegenChange::Deleted { id });
+    }
+
+    Ok(())
+}
+
+/// Pinned entries are listed first and are never removed by pruning.
+#[tauri::command]
+pub async fn set_codegen_pinned(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+    pinned: bool,
+) -> Result<(), AppError> {
+    let updated = db
+        .write(move |conn| {
+            Ok(conn.execute(
+                "UPDATE codegen_history SET is_pinned = ?1 WHERE id = ?2",
+                (pinned, id),
+            )?)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
-    let mut entries = Vec::new();
-    for r in rows {
-        entries.push(r.map_err(|e| e.to_string())?);
+    if updated == 0 {
+        return Err(AppError::NotFound(format!(
+            "Codegen history entry {} not found",
+            id
+        )));
     }
 
-    Ok(entries)
+    emit_codegen_updated(&app, CodegenChange::Pinned { id, pinned });
+
+    Ok(())
 }
 
+/// Deletes every history entry. Refuses to run unless `confirm` is true.
+/// Returns the number of entries removed.
 #[tauri::command]
-pub fn delete_codegen_entry(app: tauri::AppHandle, id: i64) -> Result<(), String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM codegen_history WHERE id = ?1", (id,))
-        .map_err(|e| e.to_string())?;
+pub async fn clear_codegen_history(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    confirm: bool,
+) -> Result<usize, AppError> {
+    if !confirm {
+        return Err(AppError::Validation(
+            "Clearing deletes all codegen history; pass confirm: true to proceed".into(),
+        ));
+    }
+
+    let cleared = db
+        .write(|conn| Ok(conn.execute("DELETE FROM codegen_history", [])?))
+        .await?;
+
+    emit_codegen_updated(&app, CodegenChange::Cleared { count: cleared });
+
+    Ok(cleared)
+}
+
+#[tauri::command]
+pub async fn update_codegen_entry(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+    summary: String,
+    payload: String,
+) -> Result<(), AppError> {
+    validate_payload(&payload)?;
+    let (stored, compressed) = encode_payload(&payload)?;
+
+    let updated = db
+        .write(move |conn| {
+            Ok(conn.execute(
+                "UPDATE codegen_history SET summary = ?1, payload = ?2, compressed = ?3
+                 WHERE id = ?4",
+                (&summary, &stored, compressed, id),
+            )?)
+        })
+        .await?;
+
+    if updated == 0 {
+        return Err(AppError::NotFound(format!(
+            "Codegen history entry {} not found",
+            id
+        )));
+    }
+
+    emit_codegen_updated(&app, CodegenChange::Updated { id });
+
     Ok(())
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct CodegenHistoryExport {
     pub entries: Vec<CodegenHistoryEntry>,
-    pub export_date: String,
-    pub version: String,
 }
 
+/// Pretty-printed JSON unless `compact` is set.
 #[tauri::command]
 pub async fn export_codegen_history(
+    db: State<'_, Db>,
+    file_path: String,
+    compact: Option<bool>,
+) -> Result<(), AppError> {
+    let entries = db.run(|conn| query_history(conn, None, None, None)).await?;
+    ExportEnvelope::new(CodegenHistoryExport { entries })
+        .write(&file_path, compact.unwrap_or(false))
+}
+
+#[derive(Serialize)]
+pub struct CodegenImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+// Digest of everything that makes two entries the same content; ids,
+// timestamps and pins are ignored
+fn entry_hash(mode: &str, summary: &str, payload: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in [mode, summary, payload] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+// Shared by the JSON and JSON Lines imports once the file is parsed
+async fn import_entries(
+    app: &tauri::AppHandle,
+    db: &Db,
+    entries: Vec<CodegenHistoryEntry>,
+    dedupe: bool,
+) -> Result<CodegenImportResult, AppError> {
+    // Checked up front so a bad entry doesn't leave a partial import behind
+    for entry in &entries {
+        CodegenMode::parse(&entry.mode)?;
+    }
+
+    let result = db
+        .write(move |conn| {
+            // One transaction, so a retry after a busy error can't leave
+            // half the file imported
+            let tx = conn.transaction()?;
+
+            let mut seen = HashSet::new();
+            if dedupe {
+                for entry in query_history(&tx, None, None, None)? {
+                    seen.insert(entry_hash(&entry.mode, &entry.summary, &entry.payload));
+                }
+            }
+
+            let mut result = CodegenImportResult {
+                imported: 0,
+                skipped: 0,
+            };
+
+            for entry in &entries {
+                if dedupe && !seen.insert(entry_hash(&entry.mode, &entry.summary, &entry.payload)) {
+                    result.skipped += 1;
+                    continue;
+                }
+
+                insert_entry(
+                    &tx,
+                    &entry.mode,
+                    &entry.summary,
+                    &entry.payload,
+                    &entry.created_at,
+                    entry.is_pinned,
+                )
+                .map_err(|e| match e {
+                    AppError::Busy(_) => e,
+                    e => AppError::Db(format!("Failed to import entry: {}", e)),
+                })?;
+                result.imported += 1;
+            }
+
+            tx.commit()?;
+
+            Ok(result)
+        })
+        .await?;
+
+    emit_codegen_updated(
+        app,
+        CodegenChange::Imported {
+            count: result.imported,
+        },
+    );
+
+    Ok(result)
+}
+
+/// With `dedupe` set, entries whose mode, summary and payload already exist
+/// (in the database or earlier in the file) are skipped. Off by default,
+/// which appends every entry.
+#[tauri::command]
+pub async fn import_codegen_history(
     app: tauri::AppHandle,
+    db: State<'_, Db>,
     file_path: String,
-) -> Result<(), String> {
-    let entries = get_codegen_history(app.clone())?;
-    
-    let export_data = CodegenHistoryExport {
-        entries,
-        export_date: Utc::now().to_rfc3339(),
-        version: "1.0".to_string(),
-    };
+    dedupe: Option<bool>,
+) -> Result<CodegenImportResult, AppError> {
+    let export_data = ExportEnvelope::<CodegenHistoryExport>::read(&file_path)?.data;
 
-    let json = serde_json::to_string_pretty(&export_data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    import_entries(&app, &db, export_data.entries, dedupe.unwrap_or(false)).await
+}
 
-    fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+/// Same entries as `export_codegen_history`, written one per line with no
+/// surrounding envelope, so the file can be streamed, appended to, or piped
+/// through tools like `jq`.
+#[tauri::command]
+pub async fn export_codegen_history_jsonl(
+    db: State<'_, Db>,
+    file_path: String,
+) -> Result<(), AppError> {
+    let entries = db.run(|conn| query_history(conn, None, None, None)).await?;
+
+    let file = fs::File::create(&file_path)
+        .map_err(|e| AppError::Io(format!("Failed to write file: {}", e)))?;
+    let mut out = BufWriter::new(file);
+    for entry in &entries {
+        serde_json::to_writer(&mut out, entry)
+            .map_err(|e| AppError::Serde(format!("Failed to serialize data: {}", e)))?;
+        out.write_all(b"\n")
+            .map_err(|e| AppError::Io(format!("Failed to write file: {}", e)))?;
+    }
+    out.flush()
+        .map_err(|e| AppError::Io(format!("Failed to write file: {}", e)))?;
 
     Ok(())
 }
 
+/// Reads a file written by `export_codegen_history_jsonl`. Blank lines are
+/// ignored; `dedupe` works as in `import_codegen_history`.
 #[tauri::command]
-pub async fn import_codegen_history(
+pub async fn import_codegen_history_jsonl(
     app: tauri::AppHandle,
+    db: State<'_, Db>,
     file_path: String,
-) -> Result<usize, String> {
-    let json = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+    dedupe: Option<bool>,
+) -> Result<CodegenImportResult, AppError> {
+    let file = fs::File::open(&file_path)
+        .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
 
-    let export_data: CodegenHistoryExport = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let mut entries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|e| AppError::Serde(format!("Invalid entry on line {}: {}", i + 1, e)))?;
+        entries.push(entry);
+    }
+
+    import_entries(&app, &db, entries, dedupe.unwrap_or(false)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::write_setting;
+
+    fn count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM codegen_history", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    // What `save_codegen_state` does inside its transaction
+    fn save(conn: &Connection) -> usize {
+        let now = Utc::now().to_rfc3339();
+        insert_entry(conn, "single", "code", "{}", &now, false).unwrap();
+        prune_history(conn).unwrap()
+    }
 
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
-    let mut imported_count = 0;
+    #[test]
+    fn bad_stored_max_history_does_not_wipe_history() {
+        let conn = crate::db::open_in_memory().unwrap();
+        for _ in 0..3 {
+            save(&conn);
+        }
 
-    for entry in export_data.entries {
-        conn.execute(
-            "INSERT INTO codegen_history (mode, summary, payload, created_at)
-             VALUES (?1, ?2, ?3, ?4)",
-            (entry.mode, entry.summary, entry.payload, entry.created_at),
-        )
-        .map_err(|e| format!("Failed to import entry: {}", e))?;
-        imported_count += 1;
+        for raw in ["0", "-1", "abc", "\"12\""] {
+            write_setting(&conn, MAX_HISTORY_KEY, raw).unwrap();
+            assert_eq!(max_history(&conn).unwrap(), DEFAULT_MAX_HISTORY, "{}", raw);
+
+            let before = count(&conn);
+            assert_eq!(save(&conn), 0, "{}", raw);
+            assert_eq!(count(&conn), before + 1, "{}", raw);
+        }
     }
 
-    Ok(imported_count)
+    #[test]
+    fn stored_max_history_caps_unpinned_entries() {
+        let conn = crate::db::open_in_memory().unwrap();
+        write_setting(&conn, MAX_HISTORY_KEY, "2").unwrap();
+        for _ in 0..3 {
+            save(&conn);
+        }
+        assert_eq!(count(&conn), 2);
+    }
 }
@@ -1,5 +1,12 @@
+use crate::db::{normalize_timestamp, Db};
+use crate::error::AppError;
+use crate::export_format::ExportEnvelope;
+use crate::tools::slug::collapse_to_separator;
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use tauri::{Emitter, State};
 
 #[derive(Serialize, Deserialize)]
 pub struct Task {
@@ -12,163 +19,615 @@ pub struct Task {
     pub created_at: String,
 }
 
-#[tauri::command]
-pub fn save_task(
-    app: tauri::AppHandle,
-    name: String,
-    number: String,
-    feature_type: String,
-    branch: String,
-    pr_title: String,
-) -> Result<(), String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
+pub const TASKS_UPDATED_EVENT: &str = "tasks-updated";
 
-    let now = chrono::Utc::now().to_rfc3339();
+// Payload of `tasks-updated`, e.g. `{ "kind": "deleted", "id": 3 }`
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TasksChange {
+    Saved { id: i64 },
+    Deleted { id: i64 },
+    Imported { count: usize },
+    Pruned { count: usize },
+    Reset,
+}
 
+pub(crate) fn emit_tasks_updated(app: &tauri::AppHandle, change: TasksChange) {
+    if let Err(e) = app.emit(TASKS_UPDATED_EVENT, change) {
+        eprintln!("Failed to emit {TASKS_UPDATED_EVENT}: {e}");
+    }
+}
+
+const TASK_COLUMNS: &str = "id, name, number, feature_type, branch, pr_title, created_at";
+
+fn row_to_task(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        number: row.get(2)?,
+        feature_type: row.get(3)?,
+        branch: row.get(4)?,
+        pr_title: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+pub(crate) fn insert_task(
+    conn: &Connection,
+    name: &str,
+    number: &str,
+    feature_type: &str,
+    branch: &str,
+    pr_title: &str,
+    created_at: &str,
+) -> Result<i64, AppError> {
     conn.execute(
         "INSERT INTO tasks (name, number, feature_type, branch, pr_title, created_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        (name, number, feature_type, branch, pr_title, now),
+        (
+            name,
+            number,
+            feature_type,
+            branch,
+            pr_title,
+            normalize_timestamp(created_at),
+        ),
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub(crate) fn query_tasks(conn: &Connection) -> Result<Vec<Task>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM tasks ORDER BY created_at DESC",
+        TASK_COLUMNS
+    ))?;
+
+    let task_iter = stmt.query_map([], row_to_task)?;
+
+    let mut tasks = Vec::new();
+
+    for task in task_iter {
+        tasks.push(task?);
+    }
+
+    Ok(tasks)
+}
+
+fn query_last_task(conn: &Connection) -> Result<Option<Task>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            "SELECT {} FROM tasks ORDER BY created_at DESC, id DESC LIMIT 1",
+            TASK_COLUMNS
+        ),
+        [],
+        row_to_task,
+    );
+
+    match result {
+        Ok(task) => Ok(Some(task)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Upper bound for `get_recent_tasks`; anything bigger should use `get_tasks`
+const MAX_RECENT_TASKS: i64 = 100;
+
+fn query_recent_tasks(conn: &Connection, n: i64) -> Result<Vec<Task>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM tasks ORDER BY created_at DESC, id DESC LIMIT ?1",
+        TASK_COLUMNS
+    ))?;
+
+    let tasks = stmt
+        .query_map((n,), row_to_task)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(tasks)
+}
+
+fn query_task(conn: &Connection, id: i64) -> Result<Task, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM tasks WHERE id = ?1", TASK_COLUMNS),
+        (id,),
+        row_to_task,
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            AppError::NotFound(format!("Task {} not found", id))
+        }
+        e => e.into(),
+    })
+}
+
+// Setting that turns branch-name checks off; on unless set to `false`
+const VALIDATE_BRANCH_NAMES_KEY: &str = "validate_branch_names";
+
+fn branch_validation_enabled(conn: &Connection) -> Result<bool, AppError> {
+    match crate::settings::read_setting(conn, VALIDATE_BRANCH_NAMES_KEY)? {
+        Some(value) => Ok(serde_json::from_str(&value)?),
+        None => Ok(true),
+    }
+}
+
+// The rules of `git check-ref-format` that apply to a branch name. An empty
+// branch means none was entered and is allowed.
+fn validate_branch_name(branch: &str) -> Result<(), AppError> {
+    if branch.is_empty() {
+        return Ok(());
+    }
+
+    let invalid = |reason: &str| {
+        Err(AppError::Validation(format!(
+            "Invalid branch name '{}': {}",
+            branch, reason
+        )))
+    };
+
+    if let Some(c) = branch
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control() || "~^:?*[\\".contains(*c))
+    {
+        return invalid(&format!("contains {:?}", c));
+    }
+    if branch.contains("..") {
+        return invalid("contains '..'");
+    }
+    if branch.contains("@{") || branch == "@" {
+        return invalid("'@{' and a lone '@' are reserved");
+    }
+    if branch.starts_with('-') {
+        return invalid("must not start with '-'");
+    }
+    if branch.ends_with('.') {
+        return invalid("must not end with '.'");
+    }
+    for part in branch.split('/') {
+        if part.is_empty() {
+            return invalid("has an empty path segment");
+        }
+        if part.starts_with('.') || part.ends_with(".lock") {
+            return invalid("path segments must not start with '.' or end with '.lock'");
+        }
+    }
 
     Ok(())
 }
 
+// Same normalization the branch-name generator applies to the task name
+fn normalize_name(name: &str) -> String {
+    collapse_to_separator(name, '-')
+}
+
+fn task_field(task: &Task, field: &str) -> Option<String> {
+    Some(match field {
+        "id" => task.id.to_string(),
+        "name" => task.name.clone(),
+        "normalized_name" => normalize_name(&task.name),
+        "number" => task.number.clone(),
+        "feature_type" => task.feature_type.clone(),
+        "branch" => task.branch.clone(),
+        "pr_title" => task.pr_title.clone(),
+        "created_at" => task.created_at.clone(),
+        _ => return None,
+    })
+}
+
+/// Replaces `{field}` placeholders with the task's values. `{{` and `}}`
+/// produce literal braces.
+fn render_task_template(task: &Task, template: &str) -> Result<String, AppError> {
+    let mut out = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => field.push(c),
+                        None => {
+                            return Err(AppError::Validation("Unclosed '{' in template".into()))
+                        }
+                    }
+                }
+                let value = task_field(task, field.trim()).ok_or_else(|| {
+                    AppError::Validation(format!("Unknown placeholder {{{}}}", field))
+                })?;
+                out.push_str(&value);
+            }
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+#[tauri::command]
+pub async fn format_task_for_clipboard(
+    db: State<'_, Db>,
+    id: i64,
+    template: String,
+) -> Result<String, AppError> {
+    let task = db.run(move |conn| query_task(conn, id)).await?;
+    render_task_template(&task, &template)
+}
+
 #[tauri::command]
-pub fn delete_task(app: tauri::AppHandle, id: i64) -> Result<(), String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM tasks WHERE id = ?1", (id,))
-        .map_err(|e| e.to_string())?;
+pub async fn save_task(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    name: String,
+    number: String,
+    feature_type: String,
+    branch: String,
+    pr_title: String,
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let id = db
+        .write(move |conn| {
+            if branch_validation_enabled(conn)? {
+                validate_branch_name(&branch)?;
+            }
+
+            insert_task(
+                conn,
+                &name,
+                &number,
+                &feature_type,
+                &branch,
+                &pr_title,
+                &now,
+            )
+        })
+        .await?;
+
+    emit_tasks_updated(&app, TasksChange::Saved { id });
+
     Ok(())
 }
 
+/// Inserts a copy of task `id` with a new id and the current time as
+/// `created_at`, and returns it. `append_copy` adds " (copy)" to the name.
 #[tauri::command]
-pub fn get_tasks(app: tauri::AppHandle) -> Result<Vec<Task>, String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, name, number, feature_type, branch, pr_title, created_at
-             FROM tasks
-             ORDER BY created_at DESC",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let task_iter = stmt
-        .query_map([], |row| {
-            Ok(Task {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                number: row.get(2)?,
-                feature_type: row.get(3)?,
-                branch: row.get(4)?,
-                pr_title: row.get(5)?,
-                created_at: row.get(6)?,
-            })
+pub async fn duplicate_task(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+    append_copy: Option<bool>,
+) -> Result<Task, AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let task = db
+        .write(move |conn| {
+            let source = query_task(conn, id)?;
+            let name = if append_copy.unwrap_or(false) {
+                format!("{} (copy)", source.name)
+            } else {
+                source.name
+            };
+
+            let new_id = insert_task(
+                conn,
+                &name,
+                &source.number,
+                &source.feature_type,
+                &source.branch,
+                &source.pr_title,
+                &now,
+            )?;
+            query_task(conn, new_id)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
-    let mut tasks = Vec::new();
+    emit_tasks_updated(&app, TasksChange::Saved { id: task.id });
 
-    for task in task_iter {
-        tasks.push(task.map_err(|e| e.to_string())?);
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn delete_task(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+) -> Result<(), AppError> {
+    let deleted = db
+        .write(move |conn| Ok(conn.execute("DELETE FROM tasks WHERE id = ?1", (id,))?))
+        .await?;
+
+    // Already gone (e.g. deleted from another window): nothing changed
+    if deleted > 0 {
+        emit_tasks_updated(&app, TasksChange::Deleted { id });
     }
 
-    Ok(tasks)
+    Ok(())
 }
 
 #[tauri::command]
-pub fn get_last_task(app: tauri::AppHandle) -> Result<Option<Task>, String> {
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, name, number, feature_type, branch, pr_title, created_at
-             FROM tasks
-             ORDER BY datetime(created_at) DESC
-             LIMIT 1",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let result = stmt.query_row([], |row| {
-        Ok(Task {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            number: row.get(2)?,
-            feature_type: row.get(3)?,
-            branch: row.get(4)?,
-            pr_title: row.get(5)?,
-            created_at: row.get(6)?,
-        })
-    });
+pub async fn get_tasks(db: State<'_, Db>) -> Result<Vec<Task>, AppError> {
+    db.run(|conn| query_tasks(conn)).await
+}
 
-    match result {
-        Ok(task) => Ok(Some(task)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string()),
+#[tauri::command]
+pub async fn get_last_task(db: State<'_, Db>) -> Result<Option<Task>, AppError> {
+    db.run(|conn| query_last_task(conn)).await
+}
+
+/// Returns the newest `n` tasks, newest first. `n` must be between 1 and
+/// `MAX_RECENT_TASKS`.
+#[tauri::command]
+pub async fn get_recent_tasks(db: State<'_, Db>, n: i64) -> Result<Vec<Task>, AppError> {
+    if !(1..=MAX_RECENT_TASKS).contains(&n) {
+        return Err(AppError::Validation(format!(
+            "n must be between 1 and {}",
+            MAX_RECENT_TASKS
+        )));
     }
+
+    db.run(move |conn| query_recent_tasks(conn, n)).await
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct TasksExport {
     pub tasks: Vec<Task>,
-    pub export_date: String,
-    pub version: String,
 }
 
+fn write_tasks_export(tasks: Vec<Task>, file_path: &str, compact: bool) -> Result<(), AppError> {
+    ExportEnvelope::new(TasksExport { tasks }).write(file_path, compact)
+}
+
+/// Pretty-printed JSON unless `compact` is set.
 #[tauri::command]
 pub async fn export_tasks(
-    app: tauri::AppHandle,
+    db: State<'_, Db>,
     file_path: String,
-) -> Result<(), String> {
-    let tasks = get_tasks(app.clone())?;
-    
-    let export_data = TasksExport {
-        tasks,
-        export_date: chrono::Utc::now().to_rfc3339(),
-        version: "1.0".to_string(),
-    };
+    compact: Option<bool>,
+) -> Result<(), AppError> {
+    let tasks = db.run(|conn| query_tasks(conn)).await?;
+    write_tasks_export(tasks, &file_path, compact.unwrap_or(false))
+}
 
-    let json = serde_json::to_string_pretty(&export_data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+/// Exports only the tasks in `ids`, in the order given. Fails without
+/// writing anything if an id doesn't exist.
+#[tauri::command]
+pub async fn export_tasks_selection(
+    db: State<'_, Db>,
+    ids: Vec<i64>,
+    file_path: String,
+    compact: Option<bool>,
+) -> Result<(), AppError> {
+    if ids.is_empty() {
+        return Err(AppError::Validation("No tasks selected".into()));
+    }
 
-    fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    let tasks = db
+        .run(move |conn| ids.iter().map(|&id| query_task(conn, id)).collect())
+        .await?;
+    write_tasks_export(tasks, &file_path, compact.unwrap_or(false))
+}
 
-    Ok(())
+#[derive(Serialize)]
+pub struct TasksImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+// Digest of the fields that identify the same piece of work, whatever its
+// id, name or timestamp
+fn task_hash(number: &str, branch: &str, pr_title: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in [number, branch, pr_title] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().into()
 }
 
+/// With `dedupe` set, tasks whose number, branch and PR title already exist
+/// (in the database or earlier in the file) are skipped. Off by default,
+/// which appends every task.
 #[tauri::command]
 pub async fn import_tasks(
     app: tauri::AppHandle,
+    db: State<'_, Db>,
     file_path: String,
-) -> Result<usize, String> {
-    let json = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
-
-    let export_data: TasksExport = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-    let conn = crate::db::get_db(&app).map_err(|e| e.to_string())?;
-    let mut imported_count = 0;
-
-    for task in export_data.tasks {
-        conn.execute(
-            "INSERT INTO tasks (name, number, feature_type, branch, pr_title, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            (
-                task.name,
-                task.number,
-                task.feature_type,
-                task.branch,
-                task.pr_title,
-                task.created_at,
-            ),
-        )
-        .map_err(|e| format!("Failed to import task: {}", e))?;
-        imported_count += 1;
-    }
-
-    Ok(imported_count)
+    dedupe: Option<bool>,
+) -> Result<TasksImportResult, AppError> {
+    let export_data = ExportEnvelope::<TasksExport>::read(&file_path)?.data;
+    let dedupe = dedupe.unwrap_or(false);
+
+    let result = db
+        .write(move |conn| {
+            // One transaction for the whole file: faster, and a bad row
+            // leaves the database untouched
+            let tx = conn.transaction()?;
+
+            let mut seen = HashSet::new();
+            if dedupe {
+                let mut stmt = tx.prepare("SELECT number, branch, pr_title FROM tasks")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok(task_hash(
+                        &row.get::<_, String>(0)?,
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ))
+                })?;
+                for r in rows {
+                    seen.insert(r?);
+                }
+            }
+
+            let mut result = TasksImportResult {
+                imported: 0,
+                skipped: 0,
+            };
+
+            for task in &export_data.tasks {
+                if dedupe && !seen.insert(task_hash(&task.number, &task.branch, &task.pr_title)) {
+                    result.skipped += 1;
+                    continue;
+                }
+
+                insert_task(
+                    &tx,
+                    &task.name,
+                    &task.number,
+                    &task.feature_type,
+                    &task.branch,
+                    &task.pr_title,
+                    &task.created_at,
+                )
+                .map_err(|e| match e {
+                    AppError::Busy(_) => e,
+                    e => AppError::Db(format!("Failed to import task: {}", e)),
+                })?;
+                result.imported += 1;
+            }
+
+            tx.commit()?;
+
+            Ok(result)
+        })
+        .await?;
+
+    emit_tasks_updated(
+        &app,
+        TasksChange::Imported {
+            count: result.imported,
+        },
+    );
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn average<T>(runs: u32, mut f: impl FnMut() -> T) -> Duration {
+        let start = Instant::now();
+        for _ in 0..runs {
+            std::hint::black_box(f());
+        }
+        start.elapsed() / runs
+    }
+
+    #[test]
+    fn accepts_valid_branch_names() {
+        for branch in [
+            "feature/x-1",
+            "bugfix/PROJ-42-login",
+            "main",
+            "release/v1.2.0",
+        ] {
+            assert!(validate_branch_name(branch).is_ok(), "{}", branch);
+        }
+    }
+
+    #[test]
+    fn empty_branch_means_none_entered() {
+        assert!(validate_branch_name("").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_branch_names() {
+        for branch in [
+            "feature..x",
+            "-feature",
+            "feature/x.lock",
+            "feature/x/",
+            "/feature",
+            "feature//x",
+            "feature.",
+            "feature/.x",
+            "feature x",
+            "feature\tx",
+            "feature\u{7f}x",
+            "feature@{1}",
+            "@",
+            "feature~1",
+            "feature:x",
+        ] {
+            assert!(
+                matches!(validate_branch_name(branch), Err(AppError::Validation(_))),
+                "{:?}",
+                branch
+            );
+        }
+    }
+
+    // Before/after for the created_at index. Run with
+    // `cargo test --release -- --ignored --nocapture last_task`.
+    #[test]
+    #[ignore]
+    fn last_task_uses_created_at_index_on_50k_rows() {
+        let mut conn = crate::db::open_in_memory().unwrap();
+        let tx = conn.transaction().unwrap();
+        let start = chrono::Utc::now() - chrono::Duration::days(365);
+        for i in 0..50_000 {
+            let created_at = (start + chrono::Duration::minutes(i)).to_rfc3339();
+            insert_task(
+                &tx,
+                "Task",
+                &i.to_string(),
+                "feature",
+                &format!("feature/{}-task", i),
+                "Task",
+                &created_at,
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+
+        let plan: Vec<String> = conn
+            .prepare(&format!(
+                "EXPLAIN QUERY PLAN SELECT {} FROM tasks ORDER BY created_at DESC, id DESC LIMIT 1",
+                TASK_COLUMNS
+            ))
+            .unwrap()
+            .query_map([], |row| row.get(3))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert!(
+            plan.iter()
+                .any(|step| step.contains("idx_tasks_created_at")),
+            "{:?}",
+            plan
+        );
+        assert!(
+            !plan.iter().any(|step| step.contains("TEMP B-TREE")),
+            "{:?}",
+            plan
+        );
+
+        // The query as it was before the index
+        let before = average(20, || {
+            conn.query_row(
+                &format!(
+                    "SELECT {} FROM tasks ORDER BY datetime(created_at) DESC LIMIT 1",
+                    TASK_COLUMNS
+                ),
+                [],
+                row_to_task,
+            )
+            .unwrap()
+        });
+        let after = average(20, || query_last_task(&conn).unwrap().unwrap());
+        println!("newest task of 50k: before {:?}, after {:?}", before, after);
+
+        assert_eq!(query_last_task(&conn).unwrap().unwrap().number, "49999");
+        assert!(after < before);
+    }
 }
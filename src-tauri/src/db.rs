@@ -1,43 +1,275 @@
-use rusqlite::{Connection, Result};
+use crate::error::AppError;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
 use tauri::path::BaseDirectory;
 use tauri::Manager;
 
-pub fn get_db(app: &tauri::AppHandle) -> Result<Connection> {
-    let db_path = app
-        .path()
-        .resolve("tasks.db", BaseDirectory::AppData)
-        .expect("failed to resolve app data path");
+// Retries of a write that hit SQLITE_BUSY, waiting 100, 200 then 400ms.
+// busy_timeout covers most contention, but SQLite returns BUSY without
+// waiting when a WAL read snapshot is stale, and only a fresh attempt helps.
+const BUSY_RETRIES: u32 = 3;
+const BUSY_FIRST_DELAY: Duration = Duration::from_millis(100);
+
+// Shared connection, opened once in `setup` and handed to commands via `State`
+#[derive(Clone)]
+pub struct Db(Arc<Mutex<Connection>>);
+
+impl Db {
+    pub fn new(conn: Connection) -> Self {
+        Db(Arc::new(Mutex::new(conn)))
+    }
+
+    // Runs `f` on the blocking thread pool so SQLite work never stalls the
+    // async runtime that drives the UI.
+    pub async fn run<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        F: FnOnce(&mut Connection) -> Result<T, AppError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut conn = db.conn()?;
+            f(&mut conn)
+        })
+        .await
+        .map_err(|e| AppError::Db(format!("Database task failed: {}", e)))?
+    }
+
+    // Like `run`, for work that writes. If SQLite reports the database busy,
+    // `f` is run again after a growing pause, so it must be safe to repeat:
+    // a single statement, or all its writes in one transaction.
+    pub async fn write<T, F>(&self, mut f: F) -> Result<T, AppError>
+    where
+        F: FnMut(&mut Connection) -> Result<T, AppError> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.run(move |conn| {
+            let mut delay = BUSY_FIRST_DELAY;
+            for _ in 0..BUSY_RETRIES {
+                match f(conn) {
+                    Err(AppError::Busy(_)) => {
+                        thread::sleep(delay);
+                        delay *= 2;
+                    }
+                    result => return result,
+                }
+            }
+            f(conn)
+        })
+        .await
+    }
+
+    pub fn conn(&self) -> Result<MutexGuard<'_, Connection>, AppError> {
+        self.0
+            .lock()
+            .map_err(|_| AppError::Db("Database connection lock poisoned".into()))
+    }
+}
+
+// Timestamps are stored as UTC RFC 3339 (`...+00:00`) so text order matches
+// time order and the `created_at` indexes can serve `ORDER BY` directly.
+// Values that don't parse are stored unchanged.
+pub fn normalize_timestamp(value: &str) -> String {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+// Passphrase for an encrypted database; only honoured by `sqlcipher` builds
+pub const PASSPHRASE_ENV: &str = "DEV_TOOLBOX_DB_PASSPHRASE";
+
+// Overrides where the database lives: a file path, or `:memory:` for a
+// throwaway in-memory database (e.g. for UI tests)
+pub const DB_PATH_ENV: &str = "DEV_TOOLBOX_DB_PATH";
+const IN_MEMORY: &str = ":memory:";
+
+/// Opens the database named by `DEV_TOOLBOX_DB_PATH`, or `tasks.db` in the
+/// app data directory, and brings its schema up to date.
+pub fn open(app: &tauri::AppHandle) -> Result<Connection, AppError> {
+    let db_path = match env::var(DB_PATH_ENV) {
+        Ok(path) if path == IN_MEMORY => return open_in_memory(),
+        Ok(path) => PathBuf::from(path),
+        Err(_) => app
+            .path()
+            .resolve("tasks.db", BaseDirectory::AppData)
+            .expect("failed to resolve app data path"),
+    };
 
     if let Some(parent) = db_path.parent() {
         fs::create_dir_all(parent).ok();
     }
 
-    let conn = Connection::open(&db_path)?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS tasks (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            number TEXT NOT NULL,
-            feature_type TEXT NOT NULL,
-            branch TEXT NOT NULL,
-            pr_title TEXT NOT NULL,
-            created_at TEXT NOT NULL
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS codegen_history (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            mode TEXT NOT NULL,        -- 'single' or 'multi'
-            summary TEXT NOT NULL,     -- short human summary
-            payload TEXT NOT NULL,     -- JSON snapshot of state
-            created_at TEXT NOT NULL   -- ISO timestamp
-        )",
-        [],
-    )?;
+    let passphrase = env::var(PASSPHRASE_ENV).ok();
+    let mut conn = open_at(&db_path, passphrase.as_deref())?;
+    crate::migrations::run(&mut conn)?;
 
     Ok(conn)
 }
+
+/// A fresh in-memory database with the full schema. Needs no `AppHandle`,
+/// so command logic can run against it outside a Tauri app via
+/// `Db::new(open_in_memory()?)`.
+pub fn open_in_memory() -> Result<Connection, AppError> {
+    let mut conn = Connection::open_in_memory()?;
+    crate::migrations::run(&mut conn)?;
+
+    Ok(conn)
+}
+
+// Opens, unlocks and configures the file without touching the schema
+pub fn open_at(path: &Path, passphrase: Option<&str>) -> Result<Connection, AppError> {
+    let conn = Connection::open(path)?;
+    if let Some(passphrase) = passphrase {
+        apply_key(&conn, passphrase)?;
+    }
+    configure(&conn)?;
+
+    Ok(conn)
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+pub fn sqlcipher_unavailable() -> AppError {
+    AppError::Validation(
+        "This build does not include SQLCipher support (enable the `sqlcipher` feature)".into(),
+    )
+}
+
+// `PRAGMA key` has to be the first statement on a fresh connection
+#[cfg(feature = "sqlcipher")]
+pub fn apply_key(conn: &Connection, passphrase: &str) -> Result<(), AppError> {
+    conn.pragma_update(None, "key", passphrase)?;
+
+    // A wrong key only shows up once something is read
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|_| AppError::Validation("Wrong database passphrase".into()))
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+pub fn apply_key(_conn: &Connection, _passphrase: &str) -> Result<(), AppError> {
+    Err(sqlcipher_unavailable())
+}
+
+// WAL lets readers and the writer work concurrently, and busy_timeout makes
+// SQLite wait for a lock instead of failing with "database is locked". It is
+// kept short because `Db::write` retries on top of it.
+fn configure(conn: &Connection) -> rusqlite::Result<()> {
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.busy_timeout(Duration::from_millis(1000))?;
+
+    let journal_mode: String = conn.pragma_query_value(None, "journal_mode", |row| row.get(0))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        eprintln!("WAL mode could not be enabled, journal_mode is {journal_mode}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{insert_task, query_tasks};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+
+    #[test]
+    fn in_memory_round_trip() {
+        let mut conn = open_in_memory().unwrap();
+        // Already migrated; running again must be a no-op
+        crate::migrations::run(&mut conn).unwrap();
+
+        let id = insert_task(
+            &conn,
+            "Login page",
+            "42",
+            "feature",
+            "feature/42-login-page",
+            "[42] Login page",
+            "2024-05-01T12:00:00+02:00",
+        )
+        .unwrap();
+
+        let tasks = query_tasks(&conn).unwrap();
+        assert_eq!(tasks.len(), 1);
+        let task = &tasks[0];
+        assert_eq!(task.id, id);
+        assert_eq!(task.name, "Login page");
+        assert_eq!(task.number, "42");
+        assert_eq!(task.feature_type, "feature");
+        assert_eq!(task.branch, "feature/42-login-page");
+        assert_eq!(task.pr_title, "[42] Login page");
+        assert_eq!(task.created_at, "2024-05-01T10:00:00+00:00");
+    }
+
+    // A file database plus a `Db` on a second connection to it. The `Db`
+    // waits only 50ms per attempt so the retries are quick to observe.
+    fn two_connections(name: &str) -> (PathBuf, Connection, Db) {
+        let path = env::temp_dir().join(format!("dev-toolbox-{}-{}.db", name, std::process::id()));
+        let mut holder = open_at(&path, None).unwrap();
+        crate::migrations::run(&mut holder).unwrap();
+
+        let conn = open_at(&path, None).unwrap();
+        conn.busy_timeout(Duration::from_millis(50)).unwrap();
+        (path, holder, Db::new(conn))
+    }
+
+    fn remove_db(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+        }
+    }
+
+    // Counts attempts at inserting one task
+    fn insert(db: &Db, attempts: &Arc<AtomicUsize>) -> Result<i64, AppError> {
+        let attempts = attempts.clone();
+        tauri::async_runtime::block_on(db.write(move |conn| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            insert_task(conn, "Task", "1", "feature", "feature/1-task", "Task", "")
+        }))
+    }
+
+    #[test]
+    fn write_retries_until_lock_is_released() {
+        let (path, holder, db) = two_connections("busy-retry");
+        holder.execute_batch("BEGIN IMMEDIATE").unwrap();
+        // Released during the second pause (attempts start at 0, ~150 and
+        // ~400ms)
+        let releaser = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            holder.execute_batch("COMMIT").unwrap();
+        });
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let result = insert(&db, &attempts);
+        releaser.join().unwrap();
+        remove_db(&path);
+
+        assert!(result.is_ok(), "{:?}", result.err().map(|e| e.to_string()));
+        assert!(attempts.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn write_gives_up_with_busy_after_retries() {
+        let (path, holder, db) = two_connections("busy-give-up");
+        let (release, released) = mpsc::channel::<()>();
+        holder.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let releaser = thread::spawn(move || {
+            released.recv().ok();
+            holder.execute_batch("COMMIT").unwrap();
+        });
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let result = insert(&db, &attempts);
+        release.send(()).unwrap();
+        releaser.join().unwrap();
+        remove_db(&path);
+
+        assert!(matches!(result, Err(AppError::Busy(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), BUSY_RETRIES as usize + 1);
+    }
+}
@@ -0,0 +1,64 @@
+use rusqlite::ErrorCode;
+use serde::Serialize;
+use std::fmt;
+
+// Serialized as `{ "kind": "NotFound", "message": "..." }` so the frontend
+// can branch on `kind` instead of parsing message strings.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum AppError {
+    Db(String),
+    Io(String),
+    Serde(String),
+    Validation(String),
+    NotFound(String),
+    /// Another connection held the lock for longer than we were willing to
+    /// wait; the operation can be retried
+    Busy(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Db(msg) => write!(f, "Database error: {}", msg),
+            AppError::Io(msg) => write!(f, "I/O error: {}", msg),
+            AppError::Serde(msg) => write!(f, "Serialization error: {}", msg),
+            AppError::Validation(msg) => write!(f, "{}", msg),
+            AppError::NotFound(msg) => write!(f, "{}", msg),
+            AppError::Busy(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound("No matching row".into()),
+            rusqlite::Error::SqliteFailure(err, _)
+                if matches!(
+                    err.code,
+                    ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked
+                ) =>
+            {
+                AppError::Busy(
+                    "The database is locked by another program; try again in a moment".into(),
+                )
+            }
+            e => AppError::Db(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Serde(e.to_string())
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::AppError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+
+// Version written by the exporters. Bump it when the file layout changes and
+// teach `upgrade_export` how to read the previous one.
+pub const CURRENT_VERSION: &str = "1.0";
+
+// "1", "1.0" and "1.0.0" are the same version; missing parts count as 0
+fn parse_version(version: &str) -> Option<[u32; 3]> {
+    let mut parts = [0; 3];
+    let mut iter = version.trim().split('.');
+    for part in parts.iter_mut() {
+        if let Some(text) = iter.next() {
+            *part = text.parse().ok()?;
+        }
+    }
+    iter.next().is_none().then_some(parts)
+}
+
+/// Brings a parsed export file up to the current layout so it can be
+/// deserialized into an `ExportEnvelope`.
+pub fn upgrade_export(mut value: Value) -> Result<Value, AppError> {
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| AppError::Validation("Export file must be a JSON object".into()))?;
+    let version = obj
+        .get("version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| AppError::Validation("Export file has no version".into()))?
+        .to_string();
+    let current = parse_version(CURRENT_VERSION).expect("CURRENT_VERSION is a valid version");
+
+    match parse_version(&version) {
+        Some(found) if found > current => Err(AppError::Validation(format!(
+            "Export file version {} is newer than this app supports ({}), please update the app",
+            version, CURRENT_VERSION
+        ))),
+        // 1.0 is the first layout the app ever wrote, so nothing needs
+        // upgrading yet; only the spelling of the version is normalized
+        Some(found) if found >= [1, 0, 0] => {
+            obj.insert("version".into(), json!(CURRENT_VERSION));
+            Ok(value)
+        }
+        _ => Err(AppError::Validation(format!(
+            "Unknown export file version {}",
+            version
+        ))),
+    }
+}
+
+/// Metadata shared by every export file, with the rows of `T` flattened in
+/// beside it: `{ "tasks": [...], "export_date": ..., "version": ... }`.
+#[derive(Serialize, Deserialize)]
+pub struct ExportEnvelope<T> {
+    #[serde(flatten)]
+    pub data: T,
+    pub export_date: String,
+    pub version: String,
+}
+
+impl<T> ExportEnvelope<T> {
+    /// Stamps `data` with the current time and format version.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            export_date: chrono::Utc::now().to_rfc3339(),
+            version: CURRENT_VERSION.to_string(),
+        }
+    }
+}
+
+impl<T: Serialize> ExportEnvelope<T> {
+    /// Pretty-printed unless `compact`, which drops the indentation to keep
+    /// large exports small.
+    pub fn write(&self, file_path: &str, compact: bool) -> Result<(), AppError> {
+        let json = if compact {
+            serde_json::to_string(self)
+        } else {
+            serde_json::to_string_pretty(self)
+        }
+        .map_err(|e| AppError::Serde(format!("Failed to serialize data: {}", e)))?;
+
+        fs::write(file_path, json)
+            .map_err(|e| AppError::Io(format!("Failed to write file: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+impl<T: DeserializeOwned> ExportEnvelope<T> {
+    /// Reads an export file of any supported version.
+    pub fn read(file_path: &str) -> Result<Self, AppError> {
+        let json = fs::read_to_string(file_path)
+            .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
+
+        let value: Value = serde_json::from_str(&json)
+            .map_err(|e| AppError::Serde(format!("Failed to parse JSON: {}", e)))?;
+        let value = upgrade_export(value)?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Serde(format!("Invalid export file: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::TasksExport;
+    use std::path::PathBuf;
+
+    // What the first release wrote: the same wrapper, hand-rolled
+    const BASELINE_EXPORT: &str = r#"{
+        "tasks": [{
+            "id": 7,
+            "name": "Login page",
+            "number": "42",
+            "feature_type": "feature",
+            "branch": "feature/42-login-page",
+            "pr_title": "[42] Login page",
+            "created_at": "2024-05-01T10:00:00+00:00"
+        }],
+        "export_date": "2024-05-02T08:30:00+00:00",
+        "version": "1.0"
+    }"#;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "dev-toolbox-export-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn with_version(version: &str) -> Value {
+        let mut value: Value = serde_json::from_str(BASELINE_EXPORT).unwrap();
+        value["version"] = json!(version);
+        value
+    }
+
+    #[test]
+    fn reads_current_version() {
+        let path = temp_file("current", "");
+        ExportEnvelope::new(TasksExport { tasks: vec![] })
+            .write(path.to_str().unwrap(), false)
+            .unwrap();
+
+        let export = ExportEnvelope::<TasksExport>::read(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(export.version, CURRENT_VERSION);
+        assert!(export.data.tasks.is_empty());
+    }
+
+    #[test]
+    fn reads_baseline_file() {
+        let path = temp_file("baseline", BASELINE_EXPORT);
+        let export = ExportEnvelope::<TasksExport>::read(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(export.export_date, "2024-05-02T08:30:00+00:00");
+        assert_eq!(export.data.tasks.len(), 1);
+        assert_eq!(export.data.tasks[0].branch, "feature/42-login-page");
+    }
+
+    #[test]
+    fn accepts_other_spellings_of_known_versions() {
+        for version in ["1", "1.0.0"] {
+            let upgraded = upgrade_export(with_version(version)).unwrap();
+            assert_eq!(upgraded["version"], CURRENT_VERSION, "version {}", version);
+        }
+    }
+
+    #[test]
+    fn rejects_future_version() {
+        let err = upgrade_export(with_version("2.0")).unwrap_err();
+        assert!(
+            matches!(&err, AppError::Validation(msg) if msg.contains("newer")),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_missing_versions() {
+        for version in ["0.9", "one"] {
+            let err = upgrade_export(with_version(version)).unwrap_err();
+            assert!(
+                matches!(&err, AppError::Validation(msg) if msg.contains("Unknown")),
+                "{}",
+                err
+            );
+        }
+
+        let mut value = with_version("1.0");
+        value.as_object_mut().unwrap().remove("version");
+        assert!(upgrade_export(value).is_err());
+        assert!(upgrade_export(json!([])).is_err());
+    }
+}
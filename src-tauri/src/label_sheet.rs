@@ -0,0 +1,143 @@
+use crate::barcodes::{render_png, CodeKind};
+use crate::error::AppError;
+use printpdf::{ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, Mm, PdfDocument, Px};
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    A4,
+    Letter,
+}
+
+impl PageSize {
+    // Width and height in millimetres, portrait
+    fn dimensions(self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LabelSheetLayout {
+    pub page_size: PageSize,
+    pub columns: u32,
+    pub rows: u32,
+    /// Page margin in millimetres
+    pub margin: f32,
+}
+
+// Gap kept between a code and the edge of its cell, in millimetres
+const CELL_PADDING: f32 = 2.0;
+
+fn validate_layout(layout: &LabelSheetLayout) -> Result<(), AppError> {
+    if layout.columns == 0 || layout.rows == 0 {
+        return Err(AppError::Validation(
+            "Columns and rows must be at least 1".into(),
+        ));
+    }
+
+    let (width, height) = layout.page_size.dimensions();
+    if layout.margin < 0.0 || layout.margin * 2.0 >= width.min(height) {
+        return Err(AppError::Validation(format!(
+            "Margin must be between 0 and {} mm",
+            width.min(height) / 2.0
+        )));
+    }
+
+    Ok(())
+}
+
+// Decodes a rendered PNG into an 8-bit greyscale image printpdf can embed
+fn to_xobject(png: &[u8]) -> Result<ImageXObject, AppError> {
+    let img = image::load_from_memory(png)
+        .map_err(|e| AppError::Validation(format!("Failed to decode barcode image: {}", e)))?
+        .to_luma8();
+
+    Ok(ImageXObject {
+        width: Px(img.width() as usize),
+        height: Px(img.height() as usize),
+        color_space: ColorSpace::Greyscale,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: false,
+        image_data: img.into_raw(),
+        image_filter: None,
+        smask: None,
+        clipping_bbox: None,
+    })
+}
+
+/// Lays the codes out on a grid across as many pages as needed and writes
+/// the PDF to `file_path`. Returns the path that was written.
+#[tauri::command]
+pub fn generate_label_sheet_pdf(
+    items: Vec<(CodeKind, String)>,
+    layout: LabelSheetLayout,
+    file_path: String,
+) -> Result<String, AppError> {
+    validate_layout(&layout)?;
+    if items.is_empty() {
+        return Err(AppError::Validation("No codes to print".into()));
+    }
+
+    let (page_w, page_h) = layout.page_size.dimensions();
+    let cell_w = (page_w - 2.0 * layout.margin) / layout.columns as f32;
+    let cell_h = (page_h - 2.0 * layout.margin) / layout.rows as f32;
+    let max_w = (cell_w - 2.0 * CELL_PADDING).max(1.0);
+    let max_h = (cell_h - 2.0 * CELL_PADDING).max(1.0);
+    let per_page = (layout.columns * layout.rows) as usize;
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Label sheet", Mm(page_w), Mm(page_h), "Labels");
+    let mut layer = doc.get_page(first_page).get_layer(first_layer);
+
+    for (index, (kind, data)) in items.iter().enumerate() {
+        let slot = index % per_page;
+        if index > 0 && slot == 0 {
+            let (page, page_layer) = doc.add_page(Mm(page_w), Mm(page_h), "Labels");
+            layer = doc.get_page(page).get_layer(page_layer);
+        }
+
+        let png = render_png(kind, data, None)
+            .map_err(|e| AppError::Validation(format!("Label {}: {}", index + 1, e)))?;
+        let xobject = to_xobject(&png)?;
+
+        // Pick the DPI that makes the image fill the cell without distortion
+        let px_w = xobject.width.0 as f32;
+        let px_h = xobject.height.0 as f32;
+        let dpi = (px_w * 25.4 / max_w).max(px_h * 25.4 / max_h);
+        let img_w = px_w * 25.4 / dpi;
+        let img_h = px_h * 25.4 / dpi;
+
+        let col = (slot % layout.columns as usize) as f32;
+        let row = (slot / layout.columns as usize) as f32;
+
+        // PDF coordinates start at the bottom-left corner
+        let cell_x = layout.margin + col * cell_w;
+        let cell_top = page_h - layout.margin - row * cell_h;
+        let x = cell_x + (cell_w - img_w) / 2.0;
+        let y = cell_top - cell_h + (cell_h - img_h) / 2.0;
+
+        Image::from(xobject).add_to_layer(
+            layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(x)),
+                translate_y: Some(Mm(y)),
+                dpi: Some(dpi),
+                ..Default::default()
+            },
+        );
+    }
+
+    let bytes = doc
+        .save_to_bytes()
+        .map_err(|e| AppError::Io(format!("Failed to build PDF: {}", e)))?;
+
+    fs::write(&file_path, bytes)
+        .map_err(|e| AppError::Io(format!("Failed to write file: {}", e)))?;
+
+    Ok(file_path)
+}
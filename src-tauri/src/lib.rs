@@ -1,7 +1,21 @@
+mod barcode_cache;
+mod barcode_grid;
 mod barcodes;
+mod bitmap_font;
 mod codegen_history;
 mod commands;
 mod db;
+mod epc_qr;
+mod error;
+mod export_format;
+mod label_sheet;
+mod maintenance;
+mod migrations;
+mod qr_sequence;
+mod settings;
+mod tools;
+
+use tauri::Manager;
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -13,20 +27,83 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            let conn = db::open(app.handle())?;
+            // A bad setting shouldn't stop the app starting
+            let capacity =
+                barcode_cache::capacity(&conn).unwrap_or(barcode_cache::DEFAULT_CAPACITY);
+            app.manage(db::Db::new(conn));
+            app.manage(barcode_cache::BarcodeCache::new(capacity));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             commands::save_task,
             commands::get_tasks,
             commands::delete_task,
+            commands::duplicate_task,
             commands::get_last_task,
+            commands::get_recent_tasks,
+            commands::format_task_for_clipboard,
             commands::export_tasks,
+            commands::export_tasks_selection,
             commands::import_tasks,
             barcodes::generate_barcode,
+            barcodes::validate_ean13,
+            barcode_cache::clear_barcode_cache,
+            barcode_cache::get_barcode_cache_capacity,
+            barcode_cache::set_barcode_cache_capacity,
+            epc_qr::generate_epc_qr,
+            qr_sequence::generate_qr_sequence,
+            barcode_grid::compose_barcode_grid,
+            label_sheet::generate_label_sheet_pdf,
+            tools::json::format_json,
+            tools::base64::base64_encode,
+            tools::base64::base64_decode,
+            tools::hash::hash_text,
+            tools::hash::hash_file,
+            tools::uuid::generate_uuid,
+            tools::timestamp::convert_timestamp,
+            tools::regex::test_regex,
+            tools::jwt::decode_jwt,
+            tools::url::url_encode,
+            tools::url::url_decode,
+            tools::html::html_encode,
+            tools::html::html_decode,
+            tools::case::convert_case,
+            tools::number_base::convert_number_base,
+            tools::password::generate_password,
+            tools::lorem::generate_lorem,
+            tools::cron::describe_cron,
+            tools::diff::text_diff,
+            tools::slug::slugify,
+            tools::color::convert_color,
             codegen_history::save_codegen_state,
             codegen_history::get_codegen_history,
+            codegen_history::count_codegen_history,
+            codegen_history::get_codegen_entry,
+            codegen_history::get_latest_codegen,
+            codegen_history::diff_codegen_entries,
             codegen_history::delete_codegen_entry,
+            codegen_history::clear_codegen_history,
+            codegen_history::update_codegen_entry,
+            codegen_history::set_codegen_pinned,
+            codegen_history::get_codegen_max_history,
+            codegen_history::set_codegen_max_history,
             codegen_history::export_codegen_history,
             codegen_history::import_codegen_history,
+            codegen_history::export_codegen_history_jsonl,
+            codegen_history::import_codegen_history_jsonl,
+            maintenance::backup_database,
+            maintenance::check_database_integrity,
+            maintenance::reset_database,
+            maintenance::vacuum_database,
+            maintenance::prune_old_records,
+            maintenance::set_db_passphrase,
+            maintenance::change_db_passphrase,
+            settings::get_setting,
+            settings::set_setting,
+            settings::get_all_settings,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
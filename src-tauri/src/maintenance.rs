@@ -0,0 +1,266 @@
+use crate::codegen_history::{emit_codegen_updated, CodegenChange};
+use crate::commands::{emit_tasks_updated, TasksChange};
+use crate::db::Db;
+use crate::error::AppError;
+use crate::migrations;
+use chrono::{Duration, Utc};
+use rusqlite::{Connection, MAIN_DB};
+use serde::Serialize;
+use std::fs;
+#[cfg(feature = "sqlcipher")]
+use std::path::PathBuf;
+use tauri::State;
+
+/// Copies the live database to `dest_path` using SQLite's online backup API,
+/// which is safe while the app keeps reading and writing. Returns the size of
+/// the written file in bytes.
+#[tauri::command]
+pub async fn backup_database(db: State<'_, Db>, dest_path: String) -> Result<u64, AppError> {
+    db.run(move |conn| {
+        conn.backup(MAIN_DB, &dest_path, None)?;
+
+        let size = fs::metadata(&dest_path)
+            .map_err(|e| AppError::Io(format!("Failed to read backup file: {}", e)))?
+            .len();
+
+        Ok(size)
+    })
+    .await
+}
+
+/// Runs `PRAGMA integrity_check`. A healthy database yields `["ok"]`,
+/// otherwise each entry is one problem reported by SQLite.
+#[tauri::command]
+pub async fn check_database_integrity(db: State<'_, Db>) -> Result<Vec<String>, AppError> {
+    db.run(|conn| {
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut problems = Vec::new();
+        for r in rows {
+            problems.push(r?);
+        }
+
+        Ok(problems)
+    })
+    .await
+}
+
+// Main file plus its WAL, which is where VACUUM writes before a checkpoint
+fn database_size(path: &str) -> u64 {
+    let len = |p: &str| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    len(path) + len(&format!("{}-wal", path))
+}
+
+/// Rebuilds the database file with `VACUUM` so space freed by deletes is
+/// returned to the filesystem. Returns the number of bytes reclaimed.
+#[tauri::command]
+pub async fn vacuum_database(db: State<'_, Db>) -> Result<u64, AppError> {
+    db.run(|conn| {
+        // VACUUM fails inside a transaction; every command commits before
+        // releasing the connection, but check rather than assume
+        if !conn.is_autocommit() {
+            return Err(AppError::Db(
+                "Cannot vacuum while a transaction is open".into(),
+            ));
+        }
+
+        let path = conn.path().unwrap_or_default().to_string();
+        let before = database_size(&path);
+
+        conn.execute_batch("VACUUM")?;
+        // Fold the WAL back into the main file so the new size shows up
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+
+        let after = database_size(&path);
+        Ok(before.saturating_sub(after))
+    })
+    .await
+}
+
+#[derive(Serialize)]
+pub struct PrunedRecords {
+    pub tasks: usize,
+    pub codegen_history: usize,
+}
+
+/// Deletes tasks and codegen history entries created more than
+/// `older_than_days` days ago, in one transaction. Pinned codegen entries
+/// are kept. Returns how many rows each table lost.
+#[tauri::command]
+pub async fn prune_old_records(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    older_than_days: i64,
+) -> Result<PrunedRecords, AppError> {
+    if older_than_days < 1 {
+        return Err(AppError::Validation(
+            "older_than_days must be at least 1".into(),
+        ));
+    }
+
+    // created_at is stored as normalized UTC RFC 3339, so text order is
+    // time order
+    let cutoff = (Utc::now() - Duration::days(older_than_days)).to_rfc3339();
+
+    let pruned = db
+        .write(move |conn| {
+            let tx = conn.transaction()?;
+
+            let tasks = tx.execute("DELETE FROM tasks WHERE created_at < ?1", (&cutoff,))?;
+            let codegen_history = tx.execute(
+                "DELETE FROM codegen_history WHERE created_at < ?1 AND is_pinned = 0",
+                (&cutoff,),
+            )?;
+
+            tx.commit()?;
+
+            Ok(PrunedRecords {
+                tasks,
+                codegen_history,
+            })
+        })
+        .await?;
+
+    if pruned.tasks > 0 {
+        emit_tasks_updated(
+            &app,
+            TasksChange::Pruned {
+                count: pruned.tasks,
+            },
+        );
+    }
+    if pruned.codegen_history > 0 {
+        emit_codegen_updated(
+            &app,
+            CodegenChange::Pruned {
+                count: pruned.codegen_history,
+            },
+        );
+    }
+
+    Ok(pruned)
+}
+
+/// Drops every table and recreates the schema from the migrations in one
+/// transaction. Refuses to run unless `confirm` is true. Returns the number
+/// of tables that were dropped.
+#[tauri::command]
+pub async fn reset_database(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    confirm: bool,
+) -> Result<usize, AppError> {
+    if !confirm {
+        return Err(AppError::Validation(
+            "Resetting deletes all data; pass confirm: true to proceed".into(),
+        ));
+    }
+
+    let reset = db
+        .write(|conn| {
+            let tx = conn.transaction()?;
+
+            let tables: Vec<String> = tx
+                .prepare(
+                    "SELECT name FROM sqlite_master
+                     WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+                )?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+
+            for table in &tables {
+                tx.execute(&format!("DROP TABLE \"{}\"", table), [])?;
+            }
+
+            migrations::apply(&tx)?;
+            tx.commit()?;
+
+            Ok(tables.len())
+        })
+        .await?;
+
+    emit_tasks_updated(&app, TasksChange::Reset);
+    emit_codegen_updated(&app, CodegenChange::Reset);
+
+    Ok(reset)
+}
+
+/// Encrypts the database with `passphrase`. Launch the app with
+/// `DEV_TOOLBOX_DB_PASSPHRASE` set to the same value from then on.
+#[tauri::command]
+pub async fn set_db_passphrase(db: State<'_, Db>, passphrase: String) -> Result<(), AppError> {
+    if passphrase.is_empty() {
+        return Err(AppError::Validation("Passphrase must not be empty".into()));
+    }
+
+    db.run(move |conn| encrypt_in_place(conn, &passphrase))
+        .await
+}
+
+#[tauri::command]
+pub async fn change_db_passphrase(
+    db: State<'_, Db>,
+    new_passphrase: String,
+) -> Result<(), AppError> {
+    if new_passphrase.is_empty() {
+        return Err(AppError::Validation("Passphrase must not be empty".into()));
+    }
+
+    db.run(move |conn| rekey(conn, &new_passphrase)).await
+}
+
+// SQLCipher can't encrypt a plaintext file in place, so the data is exported
+// into an encrypted copy which then replaces the original file.
+#[cfg(feature = "sqlcipher")]
+fn encrypt_in_place(conn: &mut Connection, passphrase: &str) -> Result<(), AppError> {
+    let path = conn
+        .path()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| AppError::Validation("Database is not backed by a file".into()))?;
+    let tmp = path.with_extension("db.encrypting");
+    let _ = fs::remove_file(&tmp);
+
+    conn.execute(
+        "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+        (tmp.to_string_lossy(), passphrase),
+    )?;
+    conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+    conn.execute("DETACH DATABASE encrypted", [])?;
+
+    // Close the plaintext handle first so its WAL is checkpointed and removed
+    let plain = std::mem::replace(conn, Connection::open_in_memory()?);
+    plain.close().map_err(|(_, e)| e)?;
+
+    if let Err(e) = fs::rename(&tmp, &path) {
+        *conn = crate::db::open_at(&path, None)?;
+        return Err(AppError::Io(format!(
+            "Failed to replace database file: {}",
+            e
+        )));
+    }
+
+    *conn = crate::db::open_at(&path, Some(passphrase))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn encrypt_in_place(_conn: &mut Connection, _passphrase: &str) -> Result<(), AppError> {
+    Err(crate::db::sqlcipher_unavailable())
+}
+
+#[cfg(feature = "sqlcipher")]
+fn rekey(conn: &Connection, passphrase: &str) -> Result<(), AppError> {
+    // Rekeying rewrites every page, which SQLCipher doesn't support in WAL mode
+    conn.pragma_update(None, "journal_mode", "DELETE")?;
+    conn.pragma_update(None, "rekey", passphrase)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn rekey(_conn: &Connection, _passphrase: &str) -> Result<(), AppError> {
+    Err(crate::db::sqlcipher_unavailable())
+}
@@ -0,0 +1,242 @@
+use crate::db::normalize_timestamp;
+use chrono::Utc;
+use rusqlite::{Connection, Result};
+
+type Migration = fn(&Connection) -> Result<()>;
+
+// Applied in order, each exactly once. Append new entries at the end and
+// never edit one that has already shipped; its index + 1 is its version.
+const MIGRATIONS: &[Migration] = &[
+    // v1: initial schema
+    |conn| {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                number TEXT NOT NULL,
+                feature_type TEXT NOT NULL,
+                branch TEXT NOT NULL,
+                pr_title TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS codegen_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                mode TEXT NOT NULL,        -- 'single' or 'multi'
+                summary TEXT NOT NULL,     -- short human summary
+                payload TEXT NOT NULL,     -- JSON snapshot of state
+                created_at TEXT NOT NULL   -- ISO timestamp
+            );",
+        )
+    },
+    // v2: payloads above a size threshold are stored gzipped
+    |conn| {
+        add_column_if_missing(
+            conn,
+            "codegen_history",
+            "compressed",
+            "INTEGER NOT NULL DEFAULT 0", // 1 when payload is a gzip blob
+        )
+    },
+    // v3: key/value settings
+    |conn| {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL        -- JSON encoded
+            );",
+        )
+    },
+    // v4: created_at indexes; older rows (e.g. imported with a non-UTC
+    // offset) are rewritten first so text order is time order
+    |conn| {
+        normalize_created_at(conn, "tasks")?;
+        normalize_created_at(conn, "codegen_history")?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_created_at ON tasks(created_at);
+            CREATE INDEX IF NOT EXISTS idx_codegen_history_created_at
+                ON codegen_history(created_at);",
+        )
+    },
+    // v5: pinned codegen entries are listed first and never pruned
+    |conn| {
+        add_column_if_missing(
+            conn,
+            "codegen_history",
+            "is_pinned",
+            "INTEGER NOT NULL DEFAULT 0",
+        )
+    },
+];
+
+fn normalize_created_at(conn: &Connection, table: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("SELECT id, created_at FROM {}", table))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    for (id, created_at) in rows {
+        let normalized = normalize_timestamp(&created_at);
+        if normalized != created_at {
+            conn.execute(
+                &format!("UPDATE {} SET created_at = ?1 WHERE id = ?2", table),
+                (normalized, id),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// Databases created before migrations existed already have some of these
+// columns, so column adds must be idempotent.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn current_version(conn: &Connection) -> Result<usize> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Creates `schema_version` if needed and applies pending migrations. Does
+/// not open a transaction, so callers can run it inside their own.
+pub fn apply(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    let current = current_version(conn)?;
+    if current > MIGRATIONS.len() {
+        eprintln!(
+            "Database schema v{current} is newer than this build (v{})",
+            MIGRATIONS.len()
+        );
+        return Ok(());
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+        migration(conn)?;
+        conn.execute(
+            "INSERT INTO schema_version (version, applied_at) VALUES (?1, ?2)",
+            (index + 1, Utc::now().to_rfc3339()),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn run(conn: &mut Connection) -> Result<()> {
+    let tx = conn.transaction()?;
+    apply(&tx)?;
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The schema as it was before `schema_version` existed. This copy of
+    // codegen_history already has `compressed`, as some dev builds did.
+    const V0_SCHEMA: &str = "
+        CREATE TABLE tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            number TEXT NOT NULL,
+            feature_type TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            pr_title TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE TABLE codegen_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            mode TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            compressed INTEGER NOT NULL DEFAULT 0
+        );";
+
+    #[test]
+    fn migrates_v0_database_to_latest() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(V0_SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO tasks (name, number, feature_type, branch, pr_title, created_at)
+             VALUES ('Login page', '42', 'feature', 'feature/42-login-page',
+                     '[42] Login page', '2024-05-01T12:00:00+02:00')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO codegen_history (mode, summary, payload, created_at, compressed)
+             VALUES ('single', 'one code', '{}', '2024-05-01T10:00:00+00:00', 0)",
+            [],
+        )
+        .unwrap();
+
+        apply(&conn).unwrap();
+
+        assert_eq!(current_version(&conn).unwrap(), MIGRATIONS.len());
+
+        let (branch, created_at): (String, String) = conn
+            .query_row("SELECT branch, created_at FROM tasks", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(branch, "feature/42-login-page");
+        assert_eq!(created_at, "2024-05-01T10:00:00+00:00");
+
+        let (summary, compressed, is_pinned): (String, i64, i64) = conn
+            .query_row(
+                "SELECT summary, compressed, is_pinned FROM codegen_history",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (summary.as_str(), compressed, is_pinned),
+            ("one code", 0, 0)
+        );
+    }
+
+    #[test]
+    fn apply_is_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        apply(&conn).unwrap();
+        apply(&conn).unwrap();
+
+        let rows: usize = conn
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, MIGRATIONS.len());
+    }
+}
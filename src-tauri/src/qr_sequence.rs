@@ -0,0 +1,123 @@
+// src-tauri/src/qr_sequence.rs
+//
+// Splits payloads too long for one QR code across several. Each symbol
+// carries a plain-text header so any scanner app can put them back together:
+//
+//     SEQ:<id>:<index>/<total>:<chunk>
+//
+// `id` is the first 8 hex digits (uppercase) of the SHA-256 of the whole
+// payload, so symbols from different sequences can't be mixed up and the
+// reassembled data can be verified. `index` counts from 1. Concatenating the
+// chunks in index order gives back the original payload.
+
+use crate::barcodes::{check_payload_length, render_data_url, CodeKind, ImageFormat};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+// Byte-mode capacity of a version 40 symbol at the default level (M)
+const MAX_SYMBOL_BYTES: usize = 2331;
+const MAX_SYMBOLS: usize = 64;
+
+#[derive(Serialize)]
+pub struct QrSequencePart {
+    /// 1-based position in the sequence
+    pub index: usize,
+    pub total: usize,
+    pub sequence_id: String,
+    /// The symbol's full content, header included
+    pub content: String,
+    pub data_url: String,
+}
+
+fn sequence_id(payload: &str) -> String {
+    let digest = Sha256::digest(payload.as_bytes());
+    digest[..4].iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+// Splits into chunks of at most `max_bytes`, never inside a character
+fn split_chunks(payload: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = payload;
+
+    while !rest.is_empty() {
+        let mut end = max_bytes.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    chunks
+}
+
+/// Encodes `data` as a sequence of QR codes using the scheme described at
+/// the top of this file. `chunk_size` caps the bytes per symbol, header
+/// included (default and maximum 2331); smaller chunks give smaller codes
+/// that are easier to scan. Fails if `data` fits in a single QR code, since
+/// `generate_barcode` should be used then.
+#[tauri::command]
+pub fn generate_qr_sequence(
+    data: String,
+    format: ImageFormat,
+    chunk_size: Option<usize>,
+) -> Result<Vec<QrSequencePart>, String> {
+    if check_payload_length(&CodeKind::Qr, &data).is_ok() {
+        return Err("Data fits in a single QR code; use generate_barcode instead".into());
+    }
+
+    let id = sequence_id(&data);
+    // Sized for the longest possible index so every header fits
+    let header_len = format!("SEQ:{}:{}/{}:", id, MAX_SYMBOLS, MAX_SYMBOLS).len();
+
+    let chunk_size = chunk_size.unwrap_or(MAX_SYMBOL_BYTES);
+    if chunk_size > MAX_SYMBOL_BYTES {
+        return Err(format!(
+            "chunk_size must be at most {} bytes",
+            MAX_SYMBOL_BYTES
+        ));
+    }
+    // Room for the header plus one 4-byte character
+    if chunk_size < header_len + 4 {
+        return Err(format!(
+            "chunk_size must be at least {} bytes",
+            header_len + 4
+        ));
+    }
+
+    let chunks = split_chunks(&data, chunk_size - header_len);
+    let total = chunks.len();
+    if total > MAX_SYMBOLS {
+        return Err(format!(
+            "Data needs {} QR codes, the maximum is {}; use a larger chunk_size or less data",
+            total, MAX_SYMBOLS
+        ));
+    }
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let index = i + 1;
+            let content = format!("SEQ:{}:{}/{}:{}", id, index, total, chunk);
+            let data_url = render_data_url(
+                CodeKind::Qr,
+                content.clone(),
+                format,
+                None,
+                None,
+                None,
+                None,
+            )?;
+
+            Ok(QrSequencePart {
+                index,
+                total,
+                sequence_id: id.clone(),
+                content,
+                data_url,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,109 @@
+use crate::db::Db;
+use crate::error::AppError;
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tauri::State;
+
+// Values are stored as JSON text so any serde type can round-trip
+pub fn read_setting(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", (key,), |row| {
+        row.get(0)
+    })
+    .optional()
+}
+
+pub fn write_setting(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        (key, value),
+    )?;
+    Ok(())
+}
+
+fn validate_key(key: &str) -> Result<(), AppError> {
+    if key.trim().is_empty() {
+        return Err(AppError::Validation("Setting key must not be empty".into()));
+    }
+    Ok(())
+}
+
+// Keys other commands read with a fixed meaning; `set_setting` must not
+// store a value they can't use
+fn validate_value(key: &str, value: &Value) -> Result<(), AppError> {
+    match key {
+        crate::codegen_history::MAX_HISTORY_KEY => match value.as_i64() {
+            Some(n) if n >= 1 => Ok(()),
+            _ => Err(AppError::Validation(
+                "max_history must be an integer of at least 1".into(),
+            )),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_setting(db: State<'_, Db>, key: String) -> Result<Option<Value>, AppError> {
+    validate_key(&key)?;
+
+    db.run(move |conn| match read_setting(conn, &key)? {
+        Some(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+        None => Ok(None),
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_setting(db: State<'_, Db>, key: String, value: Value) -> Result<(), AppError> {
+    validate_key(&key)?;
+    validate_value(&key, &value)?;
+    let raw = serde_json::to_string(&value)?;
+
+    db.write(move |conn| {
+        write_setting(conn, &key, &raw)?;
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_all_settings(db: State<'_, Db>) -> Result<BTreeMap<String, Value>, AppError> {
+    db.run(|conn| {
+        let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key")?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut settings = BTreeMap::new();
+        for r in rows {
+            let (key, raw) = r?;
+            settings.insert(key, serde_json::from_str(&raw)?);
+        }
+
+        Ok(settings)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn max_history_must_be_a_positive_integer() {
+        let key = crate::codegen_history::MAX_HISTORY_KEY;
+        for value in [json!(0), json!(-1), json!(2.5), json!("abc"), json!(null)] {
+            assert!(
+                matches!(validate_value(key, &value), Err(AppError::Validation(_))),
+                "{}",
+                value
+            );
+        }
+        assert!(validate_value(key, &json!(1)).is_ok());
+        // Other keys take any JSON
+        assert!(validate_value("theme", &json!(0)).is_ok());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::error::AppError;
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use serde::Serialize;
+
+// Decoding accepts input with or without `=` padding since both are common
+const STANDARD: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+pub(crate) const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+fn engine(url_safe: Option<bool>) -> &'static GeneralPurpose {
+    if url_safe.unwrap_or(false) {
+        &URL_SAFE
+    } else {
+        &STANDARD
+    }
+}
+
+#[derive(Serialize)]
+pub struct Base64Decoded {
+    pub bytes: Vec<u8>,
+    /// The decoded bytes as text, when they are valid UTF-8
+    pub text: Option<String>,
+}
+
+#[tauri::command]
+pub fn base64_encode(input: String, url_safe: Option<bool>) -> String {
+    engine(url_safe).encode(input.as_bytes())
+}
+
+#[tauri::command]
+pub fn base64_decode(input: String, url_safe: Option<bool>) -> Result<Base64Decoded, AppError> {
+    let bytes = engine(url_safe)
+        .decode(input.trim())
+        .map_err(|e| AppError::Validation(format!("Invalid base64: {}", e)))?;
+    let text = String::from_utf8(bytes.clone()).ok();
+
+    Ok(Base64Decoded { bytes, text })
+}
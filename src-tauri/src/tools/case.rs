@@ -0,0 +1,79 @@
+use crate::error::AppError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStyle {
+    Camel,
+    Pascal,
+    Snake,
+    Kebab,
+    ScreamingSnake,
+}
+
+/// Splits an identifier into lowercase words. Boundaries are separators
+/// (anything not alphanumeric), a lowercase letter or digit followed by an
+/// uppercase one, and the end of an acronym (`HTTPResponse` -> `http`,
+/// `response`). Digits stay attached to the word they follow.
+fn split_words(input: &str) -> Vec<String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[tauri::command]
+pub fn convert_case(input: String, target: CaseStyle) -> Result<String, AppError> {
+    let words = split_words(&input);
+    if words.is_empty() {
+        return Err(AppError::Validation(
+            "Input contains no letters or digits".into(),
+        ));
+    }
+
+    Ok(match target {
+        CaseStyle::Snake => words.join("_"),
+        CaseStyle::Kebab => words.join("-"),
+        CaseStyle::ScreamingSnake => words.join("_").to_uppercase(),
+        CaseStyle::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+        CaseStyle::Camel => {
+            let mut out = words[0].clone();
+            out.extend(words[1..].iter().map(|w| capitalize(w)));
+            out
+        }
+    })
+}
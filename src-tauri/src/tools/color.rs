@@ -0,0 +1,231 @@
+use crate::error::AppError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+}
+
+/// An sRGB color with straight (non-premultiplied) alpha.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 0.0 (transparent) to 1.0 (opaque)
+    pub a: f64,
+}
+
+fn invalid(input: &str) -> AppError {
+    AppError::Validation(format!(
+        "'{}' is not a hex, rgb() or hsl() color",
+        input.trim()
+    ))
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`; the `#` is optional.
+/// Kept separate so other color options (e.g. barcode colors) can reuse it.
+pub(crate) fn parse_hex(input: &str) -> Result<Rgba, AppError> {
+    let hex = input.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid(input));
+    }
+
+    // Short forms repeat each digit: #f80 is #ff8800
+    let expanded: String = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return Err(invalid(input)),
+    };
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).unwrap();
+
+    Ok(Rgba {
+        r: channel(0),
+        g: channel(2),
+        b: channel(4),
+        a: if expanded.len() == 8 {
+            channel(6) as f64 / 255.0
+        } else {
+            1.0
+        },
+    })
+}
+
+// Splits `name(a, b, c)`, `name(a b c)` and `name(a b c / alpha)` into the
+// argument strings
+fn function_args<'a>(input: &'a str, names: &[&str]) -> Option<Vec<&'a str>> {
+    let input = input.trim();
+    let open = input.find('(')?;
+    let name = input[..open].trim().to_ascii_lowercase();
+    if !names.contains(&name.as_str()) {
+        return None;
+    }
+    let body = input[open + 1..].strip_suffix(')')?;
+
+    Some(
+        body.split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .collect(),
+    )
+}
+
+// A plain number, or a percentage of `full`
+fn parse_number(arg: &str, full: f64) -> Option<f64> {
+    let value = match arg.strip_suffix('%') {
+        Some(pct) => pct.parse::<f64>().ok()? / 100.0 * full,
+        None => arg.parse::<f64>().ok()?,
+    };
+    value.is_finite().then_some(value)
+}
+
+fn parse_alpha(arg: Option<&&str>) -> Option<f64> {
+    match arg {
+        Some(a) => Some(parse_number(a, 1.0)?.clamp(0.0, 1.0)),
+        None => Some(1.0),
+    }
+}
+
+fn parse_rgb(input: &str) -> Option<Rgba> {
+    let args = function_args(input, &["rgb", "rgba"])?;
+    if !(3..=4).contains(&args.len()) {
+        return None;
+    }
+    let channel = |arg: &str| parse_number(arg, 255.0).map(|v| v.round().clamp(0.0, 255.0) as u8);
+
+    Some(Rgba {
+        r: channel(args[0])?,
+        g: channel(args[1])?,
+        b: channel(args[2])?,
+        a: parse_alpha(args.get(3))?,
+    })
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+fn parse_hsl(input: &str) -> Option<Rgba> {
+    let args = function_args(input, &["hsl", "hsla"])?;
+    if !(3..=4).contains(&args.len()) {
+        return None;
+    }
+    // Hue wraps round the circle; saturation and lightness are clamped
+    let hue = args[0].strip_suffix("deg").unwrap_or(args[0]);
+    let h = hue
+        .parse::<f64>()
+        .ok()
+        .filter(|h| h.is_finite())?
+        .rem_euclid(360.0);
+    let s = parse_number(args[1], 1.0)?.clamp(0.0, 1.0);
+    let l = parse_number(args[2], 1.0)?.clamp(0.0, 1.0);
+
+    let (r, g, b) = hsl_to_rgb(h, s, l);
+    Some(Rgba {
+        r,
+        g,
+        b,
+        a: parse_alpha(args.get(3))?,
+    })
+}
+
+fn rgb_to_hsl(color: Rgba) -> (f64, f64, f64) {
+    let [r, g, b] = [color.r, color.g, color.b].map(|v| v as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l * 100.0);
+    }
+
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s * 100.0, l * 100.0)
+}
+
+// One decimal place at most, without a trailing `.0`
+fn short(value: f64) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+fn format_color(color: Rgba, target: ColorFormat) -> String {
+    let opaque = color.a >= 1.0;
+    let alpha = short_alpha(color.a);
+    match target {
+        ColorFormat::Hex if opaque => format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b),
+        ColorFormat::Hex => format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            color.r,
+            color.g,
+            color.b,
+            (color.a * 255.0).round() as u8
+        ),
+        ColorFormat::Rgb if opaque => format!("rgb({}, {}, {})", color.r, color.g, color.b),
+        ColorFormat::Rgb => format!("rgba({}, {}, {}, {})", color.r, color.g, color.b, alpha),
+        ColorFormat::Hsl => {
+            let (h, s, l) = rgb_to_hsl(color);
+            if opaque {
+                format!("hsl({}, {}%, {}%)", short(h), short(s), short(l))
+            } else {
+                format!(
+                    "hsla({}, {}%, {}%, {})",
+                    short(h),
+                    short(s),
+                    short(l),
+                    alpha
+                )
+            }
+        }
+    }
+}
+
+// Alpha keeps up to three decimals, enough for an 8-bit channel
+fn short_alpha(alpha: f64) -> String {
+    let rounded = (alpha * 1000.0).round() / 1000.0;
+    format!("{}", rounded)
+}
+
+/// Parses a hex, `rgb()`/`rgba()` or `hsl()`/`hsla()` color and prints it in
+/// `target` format. Out-of-range channels are clamped. Alpha is kept, and
+/// the alpha form (`#rrggbbaa`, `rgba()`, `hsla()`) is used only when the
+/// color is not fully opaque.
+#[tauri::command]
+pub fn convert_color(input: String, target: ColorFormat) -> Result<String, AppError> {
+    let color = if input.contains('(') {
+        parse_rgb(&input)
+            .or_else(|| parse_hsl(&input))
+            .ok_or_else(|| invalid(&input))?
+    } else {
+        parse_hex(&input)?
+    };
+
+    Ok(format_color(color, target))
+}
@@ -0,0 +1,428 @@
+use super::timestamp::parse_input;
+use ::cron::error::Error as CronParseError;
+use ::cron::Schedule;
+use chrono::{FixedOffset, TimeZone, Utc};
+use serde::Serialize;
+use std::str::FromStr;
+
+const DEFAULT_COUNT: usize = 5;
+const MAX_COUNT: usize = 100;
+
+// Field order of the `cron` crate, which always includes seconds
+const FIELD_NAMES: [&str; 6] = [
+    "second",
+    "minute",
+    "hour",
+    "day_of_month",
+    "month",
+    "day_of_week",
+];
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+// `cron` crate numbering: 1 is Sunday
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+#[derive(Serialize)]
+pub struct CronDescription {
+    pub description: String,
+    /// Upcoming fire times, RFC 3339 in the requested offset
+    pub next: Vec<String>,
+}
+
+/// `field` names the offending field (`minute`, `day_of_week`, ...) when
+/// the error can be pinned to one.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
+pub enum DescribeCronError {
+    Syntax {
+        message: String,
+        field: Option<String>,
+    },
+    Validation {
+        message: String,
+    },
+}
+
+fn validation(message: impl Into<String>) -> DescribeCronError {
+    DescribeCronError::Validation {
+        message: message.into(),
+    }
+}
+
+// The Unix days a numeric stepped item like `1-7/2` or `3/2` (to the end of
+// the week) selects. None for names, `*` and anything malformed, which are
+// left for the crate to read or reject.
+fn expand_weekday_step(base: &str, step: &str) -> Option<Vec<u32>> {
+    let step: usize = step.parse().ok().filter(|&s| s > 0)?;
+    let (start, end): (u32, u32) = match base.split_once('-') {
+        Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+        None => (base.parse().ok()?, 7),
+    };
+    (start <= end && end <= 7).then(|| (start..=end).step_by(step).collect())
+}
+
+// Unix crontab numbers weekdays 0-7 with Sunday as 0 and 7; the `cron`
+// crate uses 1-7 with Sunday as 1. Names and `*` pass through unchanged.
+fn unix_weekday_field(field: &str) -> String {
+    let shift = |n: &str| match n.parse::<u32>() {
+        Ok(7) => "1".to_string(),
+        Ok(d) => (d + 1).to_string(),
+        Err(_) => n.to_string(),
+    };
+
+    field
+        .split(',')
+        .map(|item| {
+            let (base, step) = match item.split_once('/') {
+                Some((base, step)) => (base, Some(step)),
+                None => (item, None),
+            };
+            // Shifting a stepped range would drop a Sunday it lands on as 7
+            // (`1-7/2` is Mon, Wed, Fri and Sun), so it becomes a list
+            if let Some(days) = step.and_then(|step| expand_weekday_step(base, step)) {
+                let mut days: Vec<u32> = days.iter().map(|&d| d % 7 + 1).collect();
+                days.sort_unstable();
+                days.dedup();
+                return days
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+            let base = match base.split_once('-') {
+                // A range ending on Sunday-as-7 wraps round to 1
+                Some((start, "7")) if step.is_none() && start != "0" => {
+                    format!("{}-7,1", shift(start))
+                }
+                Some((start, "7")) => format!("{}-7", shift(start)),
+                Some((start, end)) => format!("{}-{}", shift(start), shift(end)),
+                None => shift(base),
+            };
+            match step {
+                Some(step) => format!("{}/{}", base, step),
+                None => base,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// 5 fields (crontab) gain a leading `0` seconds field; 6 fields are taken
+// as the `cron` crate's own syntax
+fn to_schedule_fields(expr: &str) -> Result<Vec<String>, DescribeCronError> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    match fields.len() {
+        5 => {
+            let mut out = vec!["0".to_string()];
+            out.extend(fields[..4].iter().map(|f| f.to_string()));
+            out.push(unix_weekday_field(fields[4]));
+            Ok(out)
+        }
+        6 => Ok(fields.iter().map(|f| f.to_string()).collect()),
+        n => Err(validation(format!("Expected 5 or 6 fields, got {}", n))),
+    }
+}
+
+// The crate's parse errors don't say which field failed, so each field is
+// parsed on its own with every other field set to `*`
+fn find_bad_field(fields: &[String]) -> Option<usize> {
+    (0..fields.len()).find(|&i| {
+        let probe: Vec<&str> = (0..fields.len())
+            .map(|j| if i == j { fields[j].as_str() } else { "*" })
+            .collect();
+        Schedule::from_str(&probe.join(" ")).is_err()
+    })
+}
+
+// Error messages quote the field as the user wrote it, not the rewritten
+// six-field form the crate saw
+fn syntax_error(expr: &str, fields: &[String], error: CronParseError) -> DescribeCronError {
+    // The crate's message ends with the reason after an ASCII-art pointer
+    let reason = error.to_string();
+    let reason = reason.lines().last().unwrap_or_default().to_string();
+
+    let Some(index) = find_bad_field(fields) else {
+        return DescribeCronError::Syntax {
+            message: reason,
+            field: None,
+        };
+    };
+
+    let original: Vec<&str> = expr.split_whitespace().collect();
+    let crontab = original.len() == 5;
+    let written = if crontab {
+        original[index - 1]
+    } else {
+        original[index]
+    };
+    let name = FIELD_NAMES[index];
+
+    let message = if crontab && name == "day_of_week" {
+        format!("Invalid {} '{}': expected 0-7 or SUN-SAT", name, written)
+    } else {
+        format!("Invalid {} '{}': {}", name, written, reason)
+    };
+
+    DescribeCronError::Syntax {
+        message,
+        field: Some(name.to_string()),
+    }
+}
+
+fn join_and(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn value_name(value: &str, names: Option<(&[&str], u32)>) -> String {
+    match (value.parse::<u32>(), names) {
+        (Ok(n), Some((names, first))) => n
+            .checked_sub(first)
+            .and_then(|i| names.get(i as usize))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| value.to_string()),
+        (Ok(_), None) => value.to_string(),
+        // Names such as MON or jan
+        (Err(_), _) => {
+            let lower = value.to_lowercase();
+            let mut chars = lower.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => lower,
+            }
+        }
+    }
+}
+
+fn range_text(spec: &str, names: Option<(&[&str], u32)>) -> String {
+    match spec.split_once('-') {
+        Some((start, end)) => format!(
+            "{} through {}",
+            value_name(start, names),
+            value_name(end, names)
+        ),
+        None => value_name(spec, names),
+    }
+}
+
+/// Describes one field, e.g. "every 15 minutes" or "on Monday through
+/// Friday". `None` when the field matches everything.
+fn describe_field(
+    field: &str,
+    unit: &str,
+    units: &str,
+    prep: &str,
+    names: Option<(&[&str], u32)>,
+) -> Option<String> {
+    if field == "*" || field == "?" {
+        return None;
+    }
+
+    let items: Vec<&str> = field.split(',').collect();
+    let phrases: Vec<String> = items
+        .iter()
+        .map(|item| match item.split_once('/') {
+            Some((start, step)) => {
+                let every = if step == "1" {
+                    format!("every {}", unit)
+                } else {
+                    format!("every {} {}", step, units)
+                };
+                match start {
+                    "*" | "?" => every,
+                    start => format!("{} from {}", every, range_text(start, names)),
+                }
+            }
+            None => range_text(item, names),
+        })
+        .collect();
+
+    if let [only] = items.as_slice() {
+        if only.contains('/') {
+            return Some(phrases[0].clone());
+        }
+    }
+
+    let plural = items.len() > 1 || field.contains('-');
+    let unit = if unit.is_empty() {
+        String::new()
+    } else if plural {
+        format!("{} ", units)
+    } else {
+        format!("{} ", unit)
+    };
+    Some(format!("{} {}{}", prep, unit, join_and(&phrases)))
+}
+
+fn describe(fields: &[String]) -> String {
+    let [sec, min, hour, dom, month, dow] = [0, 1, 2, 3, 4, 5].map(|i| fields[i].as_str());
+    let mut parts = Vec::new();
+
+    match (sec.parse::<u32>(), min.parse::<u32>(), hour.parse::<u32>()) {
+        (Ok(0), Ok(m), Ok(h)) => parts.push(format!("at {:02}:{:02}", h, m)),
+        (Ok(s), Ok(m), Ok(h)) => parts.push(format!("at {:02}:{:02}:{:02}", h, m, s)),
+        _ => {
+            if sec != "0" {
+                parts.push(
+                    describe_field(sec, "second", "seconds", "at", None)
+                        .unwrap_or_else(|| "every second".into()),
+                );
+            }
+            match describe_field(min, "minute", "minutes", "at", None) {
+                Some(p) => parts.push(p),
+                None if sec == "0" => parts.push("every minute".into()),
+                None => {}
+            }
+            parts.extend(describe_field(hour, "hour", "hours", "during", None));
+        }
+    }
+
+    parts.extend(describe_field(
+        dom,
+        "day-of-month",
+        "days-of-month",
+        "on",
+        None,
+    ));
+    parts.extend(describe_field(month, "", "", "in", Some((&MONTH_NAMES, 1))));
+    parts.extend(describe_field(dow, "", "", "on", Some((&WEEKDAY_NAMES, 1))));
+
+    let sentence = parts.join(", ");
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => sentence,
+    }
+}
+
+/// Parses a 5-field crontab expression (minute to day of week, Sunday = 0)
+/// or a 6-field one with leading seconds in the `cron` crate's syntax
+/// (Sunday = 1). Returns a description and the next `count` (default 5)
+/// fire times after `base` (a unix epoch or ISO-8601 date, default now),
+/// shown at `offset_minutes` from UTC.
+#[tauri::command]
+pub fn describe_cron(
+    expr: String,
+    count: Option<usize>,
+    base: Option<String>,
+    offset_minutes: Option<i32>,
+) -> Result<CronDescription, DescribeCronError> {
+    let count = count.unwrap_or(DEFAULT_COUNT);
+    if count == 0 || count > MAX_COUNT {
+        return Err(validation(format!(
+            "Count must be between 1 and {}",
+            MAX_COUNT
+        )));
+    }
+
+    let offset = offset_minutes
+        .unwrap_or(0)
+        .checked_mul(60)
+        .and_then(FixedOffset::east_opt)
+        .ok_or_else(|| validation("Offset must be within ±24 hours"))?;
+    let base = match base {
+        Some(base) => parse_input(base.trim()).map_err(|e| validation(e.to_string()))?,
+        None => Utc::now(),
+    };
+
+    let fields = to_schedule_fields(&expr)?;
+    let schedule =
+        Schedule::from_str(&fields.join(" ")).map_err(|e| syntax_error(&expr, &fields, e))?;
+
+    let next = schedule
+        .after(&offset.from_utc_datetime(&base.naive_utc()))
+        .take(count)
+        .map(|dt| dt.to_rfc3339())
+        .collect();
+
+    Ok(CronDescription {
+        description: describe(&fields),
+        next,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_out_of_range_offsets() {
+        for offset in [1440, -1440, i32::MAX, i32::MIN] {
+            let result = describe_cron("0 9 * * 1".into(), None, Some("0".into()), Some(offset));
+            assert!(
+                matches!(result, Err(DescribeCronError::Validation { .. })),
+                "offset {}",
+                offset
+            );
+        }
+        assert!(describe_cron("0 9 * * 1".into(), None, Some("0".into()), Some(120)).is_ok());
+    }
+
+    fn weekdays(expr: &str, count: usize) -> Vec<String> {
+        // 2024-01-01 is a Monday
+        describe_cron(
+            expr.into(),
+            Some(count),
+            Some("2024-01-01T00:00:00Z".into()),
+            None,
+        )
+        .unwrap()
+        .next
+        .iter()
+        .map(|t| {
+            chrono::DateTime::parse_from_rfc3339(t)
+                .unwrap()
+                .format("%a")
+                .to_string()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn stepped_weekday_range_keeps_sunday_as_7() {
+        assert_eq!(unix_weekday_field("1-7/2"), "1,2,4,6");
+        assert_eq!(
+            weekdays("0 9 * * 1-7/2", 5),
+            ["Mon", "Wed", "Fri", "Sun", "Mon"]
+        );
+
+        assert_eq!(unix_weekday_field("5-7/2"), "1,6");
+        assert_eq!(weekdays("0 9 * * 5-7/2", 3), ["Fri", "Sun", "Fri"]);
+
+        // A step from a single day runs to the end of the week
+        assert_eq!(weekdays("0 9 * * 3/2", 3), ["Wed", "Fri", "Sun"]);
+    }
+
+    #[test]
+    fn plain_weekday_ranges_still_shift() {
+        assert_eq!(unix_weekday_field("1-5"), "2-6");
+        assert_eq!(unix_weekday_field("5-7"), "6-7,1");
+        assert_eq!(weekdays("0 9 * * 0", 1), ["Sun"]);
+        assert_eq!(weekdays("0 9 * * */2", 4), ["Tue", "Thu", "Sat", "Sun"]);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::AppError;
+use serde::Deserialize;
+use similar::udiff::UnifiedHunkHeader;
+use similar::{ChangeTag, DiffTag, TextDiff};
+use std::fmt::Write;
+
+const DEFAULT_CONTEXT: usize = 3;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffMode {
+    Unified,
+    SideBySide,
+}
+
+/// Line-level unified diff with `context` unchanged lines around each hunk.
+/// Shared with `diff_codegen_entries`.
+pub(crate) fn unified_diff(
+    a: &str,
+    b: &str,
+    header_a: &str,
+    header_b: &str,
+    context: usize,
+) -> String {
+    TextDiff::from_lines(a, b)
+        .unified_diff()
+        .context_radius(context)
+        .header(header_a, header_b)
+        .to_string()
+}
+
+// git --word-diff=plain style: removed words as [-...-], added as {+...+}
+fn inline_words(old: &str, new: &str) -> (String, String, String) {
+    let diff = TextDiff::from_words(old, new);
+    let (mut left, mut right, mut merged) = (String::new(), String::new(), String::new());
+
+    for change in diff.iter_all_changes() {
+        let value = change.value();
+        match change.tag() {
+            ChangeTag::Equal => {
+                left.push_str(value);
+                right.push_str(value);
+                merged.push_str(value);
+            }
+            ChangeTag::Delete => {
+                let marked = format!("[-{}-]", value);
+                left.push_str(&marked);
+                merged.push_str(&marked);
+            }
+            ChangeTag::Insert => {
+                let marked = format!("{{+{}+}}", value);
+                right.push_str(&marked);
+                merged.push_str(&marked);
+            }
+        }
+    }
+
+    (left, right, merged)
+}
+
+fn trim_newline(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .unwrap_or(line)
+}
+
+// Unified hunks where each replaced block is shown once, as `~` lines with
+// inline word markers, instead of as separate `-` and `+` lines
+fn unified_words(a: &str, b: &str, context: usize) -> String {
+    let diff = TextDiff::from_lines(a, b);
+    let (old, new) = (diff.old_slices(), diff.new_slices());
+    let mut out = String::from("--- a\n+++ b\n");
+
+    for group in diff.grouped_ops(context) {
+        writeln!(out, "{}", UnifiedHunkHeader::new(&group)).unwrap();
+        for op in &group {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            match tag {
+                DiffTag::Equal => {
+                    for line in &old[old_range] {
+                        writeln!(out, " {}", trim_newline(line)).unwrap();
+                    }
+                }
+                DiffTag::Delete => {
+                    for line in &old[old_range] {
+                        writeln!(out, "-{}", trim_newline(line)).unwrap();
+                    }
+                }
+                DiffTag::Insert => {
+                    for line in &new[new_range] {
+                        writeln!(out, "+{}", trim_newline(line)).unwrap();
+                    }
+                }
+                DiffTag::Replace => {
+                    let (_, _, merged) =
+                        inline_words(&old[old_range].concat(), &new[new_range].concat());
+                    for line in trim_newline(&merged).lines() {
+                        writeln!(out, "~{}", line).unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+// Rows of (marker, left, right). Markers: ' ' same, '<' only on the left,
+// '>' only on the right, '|' changed.
+fn side_by_side_rows(
+    a: &str,
+    b: &str,
+    context: usize,
+    words: bool,
+) -> Vec<Option<(char, String, String)>> {
+    let diff = TextDiff::from_lines(a, b);
+    let (old, new) = (diff.old_slices(), diff.new_slices());
+    let mut rows = Vec::new();
+
+    for (i, group) in diff.grouped_ops(context).iter().enumerate() {
+        // `None` separates hunks
+        if i > 0 {
+            rows.push(None);
+        }
+        for op in group {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            let left = &old[old_range];
+            let right = &new[new_range];
+
+            for row in 0..left.len().max(right.len()) {
+                let l = left.get(row).map(|l| trim_newline(l));
+                let r = right.get(row).map(|r| trim_newline(r));
+                rows.push(Some(match (l, r) {
+                    (Some(l), Some(r)) if tag == DiffTag::Equal => (' ', l.into(), r.into()),
+                    (Some(l), Some(r)) if words => {
+                        let (l, r, _) = inline_words(l, r);
+                        ('|', l, r)
+                    }
+                    (Some(l), Some(r)) => ('|', l.into(), r.into()),
+                    (Some(l), None) => ('<', l.into(), String::new()),
+                    (None, Some(r)) => ('>', String::new(), r.into()),
+                    (None, None) => unreachable!(),
+                }));
+            }
+        }
+    }
+
+    rows
+}
+
+fn side_by_side(a: &str, b: &str, context: usize, words: bool) -> String {
+    let rows = side_by_side_rows(a, b, context, words);
+    let width = rows
+        .iter()
+        .flatten()
+        .map(|(_, left, _)| left.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for row in rows {
+        match row {
+            Some((marker, left, right)) => {
+                let line = format!("{:<width$} {} {}", left, marker, right);
+                writeln!(out, "{}", line.trim_end()).unwrap();
+            }
+            None => writeln!(out, "{:<width$} ...", "...").unwrap(),
+        }
+    }
+    out
+}
+
+/// Diffs two texts. `unified` is a standard patch; `side_by_side` pairs the
+/// lines in two columns. With `words`, changed lines also mark the words
+/// that differ as `[-removed-]` and `{+added+}` (shown on `~` lines in
+/// unified mode). `context` (default 3) is the number of unchanged lines
+/// kept around each change. Returns an empty string when the texts match.
+#[tauri::command]
+pub fn text_diff(
+    a: String,
+    b: String,
+    mode: DiffMode,
+    words: Option<bool>,
+    context: Option<usize>,
+) -> Result<String, AppError> {
+    if a == b {
+        return Ok(String::new());
+    }
+
+    let context = context.unwrap_or(DEFAULT_CONTEXT);
+    let words = words.unwrap_or(false);
+
+    Ok(match (mode, words) {
+        (DiffMode::Unified, false) => unified_diff(&a, &b, "a", "b", context),
+        (DiffMode::Unified, true) => unified_words(&a, &b, context),
+        (DiffMode::SideBySide, words) => side_by_side(&a, &b, context, words),
+    })
+}
@@ -0,0 +1,63 @@
+use crate::error::AppError;
+use md5::Md5;
+use serde::Deserialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::fs::File;
+use std::io::Read;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+// Files are read in chunks of this size so large inputs stay out of memory
+const CHUNK_SIZE: usize = 64 * 1024;
+
+fn digest_reader<D: Digest>(mut reader: impl Read) -> Result<String, AppError> {
+    let mut hasher = D::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+fn hash_reader(algo: HashAlgo, reader: impl Read) -> Result<String, AppError> {
+    match algo {
+        HashAlgo::Md5 => digest_reader::<Md5>(reader),
+        HashAlgo::Sha1 => digest_reader::<Sha1>(reader),
+        HashAlgo::Sha256 => digest_reader::<Sha256>(reader),
+        HashAlgo::Sha512 => digest_reader::<Sha512>(reader),
+    }
+}
+
+/// Lowercase hex digest of the UTF-8 bytes of `input`.
+#[tauri::command]
+pub fn hash_text(input: String, algo: HashAlgo) -> Result<String, AppError> {
+    hash_reader(algo, input.as_bytes())
+}
+
+/// Lowercase hex digest of a file, streamed from disk on a blocking thread.
+#[tauri::command]
+pub async fn hash_file(file_path: String, algo: HashAlgo) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let file = File::open(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to open file: {}", e)))?;
+        hash_reader(algo, file)
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Hashing task failed: {}", e)))?
+}
@@ -0,0 +1,129 @@
+// The entities people actually meet in copied markup; anything else is left
+// as written when decoding
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("shy", '\u{ad}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("deg", '°'),
+    ("plusmn", '±'),
+    ("times", '×'),
+    ("divide", '÷'),
+    ("micro", 'µ'),
+    ("para", '¶'),
+    ("sect", '§'),
+    ("middot", '·'),
+    ("bull", '•'),
+    ("hellip", '…'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("euro", '€'),
+    ("pound", '£'),
+    ("yen", '¥'),
+    ("cent", '¢'),
+    ("frac12", '½'),
+    ("frac14", '¼'),
+    ("frac34", '¾'),
+    ("sup2", '²'),
+    ("sup3", '³'),
+    ("iexcl", '¡'),
+    ("iquest", '¿'),
+];
+
+// Longest name in the table, so a stray `&` doesn't scan the whole input
+const MAX_ENTITY_LEN: usize = 10;
+
+// `&#38;` / `&#x26;`. Code points that aren't valid characters (0,
+// surrogates, past U+10FFFF) become U+FFFD, as browsers do.
+fn decode_numeric(body: &str) -> Option<char> {
+    let code = match body.strip_prefix(['x', 'X']) {
+        Some(hex) if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            u32::from_str_radix(hex, 16).unwrap_or(u32::MAX)
+        }
+        None if !body.is_empty() && body.chars().all(|c| c.is_ascii_digit()) => {
+            body.parse().unwrap_or(u32::MAX)
+        }
+        _ => return None,
+    };
+
+    Some(
+        char::from_u32(code)
+            .filter(|&c| c != '\0')
+            .unwrap_or(char::REPLACEMENT_CHARACTER),
+    )
+}
+
+fn decode_entity(body: &str) -> Option<char> {
+    match body.strip_prefix('#') {
+        Some(numeric) => decode_numeric(numeric),
+        None => NAMED_ENTITIES
+            .iter()
+            .find(|(name, _)| *name == body)
+            .map(|&(_, c)| c),
+    }
+}
+
+/// Escapes `&`, `<`, `>`, `"` and `'`, which is enough for both element
+/// content and quoted attribute values.
+#[tauri::command]
+pub fn html_encode(input: String) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Replaces named (`&amp;`), decimal (`&#38;`) and hex (`&#x26;`) entities
+/// with the characters they stand for. Unknown entities and a `&` with no
+/// closing `;` are left unchanged.
+#[tauri::command]
+pub fn html_decode(input: String) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input.as_str();
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest[1..]
+            .char_indices()
+            .take(MAX_ENTITY_LEN + 1)
+            .find(|&(_, c)| c == ';')
+            .and_then(|(end, _)| Some((decode_entity(&rest[1..=end])?, end)));
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                // `&`, the body and the `;`
+                rest = &rest[end + 2..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,64 @@
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Value;
+
+const DEFAULT_INDENT: usize = 2;
+const MAX_INDENT: usize = 8;
+
+// Same `{ kind, message }` shape as `AppError`, plus the position of syntax
+// errors so the UI can point at the offending character.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
+pub enum FormatJsonError {
+    Syntax {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    Validation {
+        message: String,
+    },
+}
+
+impl From<serde_json::Error> for FormatJsonError {
+    fn from(e: serde_json::Error) -> Self {
+        FormatJsonError::Syntax {
+            message: e.to_string(),
+            line: e.line(),
+            column: e.column(),
+        }
+    }
+}
+
+/// Pretty-prints `input` with `indent` spaces (default 2), or strips all
+/// whitespace when `minify` is set. Key order is preserved.
+#[tauri::command]
+pub fn format_json(
+    input: String,
+    indent: Option<usize>,
+    minify: Option<bool>,
+) -> Result<String, FormatJsonError> {
+    let value: Value = serde_json::from_str(&input)?;
+
+    if minify.unwrap_or(false) {
+        return Ok(serde_json::to_string(&value)?);
+    }
+
+    let indent = indent.unwrap_or(DEFAULT_INDENT);
+    if indent > MAX_INDENT {
+        return Err(FormatJsonError::Validation {
+            message: format!("Indent must be at most {} spaces", MAX_INDENT),
+        });
+    }
+
+    let indent = " ".repeat(indent);
+    let mut out = Vec::new();
+    let mut ser = serde_json::Serializer::with_formatter(
+        &mut out,
+        PrettyFormatter::with_indent(indent.as_bytes()),
+    );
+    value.serialize(&mut ser)?;
+
+    // serde_json only ever writes valid UTF-8
+    Ok(String::from_utf8(out).expect("serde_json output is UTF-8"))
+}
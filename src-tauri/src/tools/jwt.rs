@@ -0,0 +1,51 @@
+use crate::error::AppError;
+use crate::tools::base64::URL_SAFE;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize)]
+pub struct DecodedJwt {
+    /// Pretty-printed header JSON
+    pub header: String,
+    /// Pretty-printed payload (claims) JSON
+    pub payload: String,
+    /// The header's `alg`, if present
+    pub algorithm: Option<String>,
+    /// Raw base64url signature segment; never verified
+    pub signature: String,
+}
+
+fn decode_segment(segment: &str, name: &str) -> Result<Value, AppError> {
+    let bytes = URL_SAFE
+        .decode(segment)
+        .map_err(|e| AppError::Validation(format!("Invalid base64 in JWT {}: {}", name, e)))?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| AppError::Validation(format!("JWT {} is not valid JSON: {}", name, e)))
+}
+
+/// Decodes a JWT's header and payload without checking the signature.
+#[tauri::command]
+pub fn decode_jwt(token: String) -> Result<DecodedJwt, AppError> {
+    let segments: Vec<&str> = token.trim().split('.').collect();
+    let [header, payload, signature] = segments[..] else {
+        return Err(AppError::Validation(format!(
+            "A JWT has 3 dot-separated segments, found {}",
+            segments.len()
+        )));
+    };
+
+    let header = decode_segment(header, "header")?;
+    let payload = decode_segment(payload, "payload")?;
+    let algorithm = header
+        .get("alg")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    Ok(DecodedJwt {
+        header: serde_json::to_string_pretty(&header)?,
+        payload: serde_json::to_string_pretty(&payload)?,
+        algorithm,
+        signature: signature.to_string(),
+    })
+}
@@ -0,0 +1,219 @@
+use crate::error::AppError;
+use rand::rngs::ThreadRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "ex",
+    "ea",
+    "commodo",
+    "consequat",
+    "duis",
+    "aute",
+    "irure",
+    "in",
+    "reprehenderit",
+    "voluptate",
+    "velit",
+    "esse",
+    "cillum",
+    "eu",
+    "fugiat",
+    "nulla",
+    "pariatur",
+    "excepteur",
+    "sint",
+    "occaecat",
+    "cupidatat",
+    "non",
+    "proident",
+    "sunt",
+    "culpa",
+    "qui",
+    "officia",
+    "deserunt",
+    "mollit",
+    "anim",
+    "id",
+    "est",
+    "laborum",
+];
+
+// The opening every designer recognises
+const CLASSIC: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet,",
+    "consectetur",
+    "adipiscing",
+    "elit",
+];
+
+const MIN_SENTENCE_WORDS: usize = 6;
+const MAX_SENTENCE_WORDS: usize = 14;
+const DEFAULT_WORDS_PER_PARAGRAPH: usize = 50;
+const MAX_COUNT: usize = 1000;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LoremUnit {
+    Words,
+    Sentences,
+    Paragraphs,
+}
+
+struct Generator {
+    rng: ThreadRng,
+    // Classic words not yet used
+    classic: &'static [&'static str],
+}
+
+impl Generator {
+    fn word(&mut self) -> &'static str {
+        match self.classic.split_first() {
+            Some((word, rest)) => {
+                self.classic = rest;
+                word
+            }
+            None => WORDS.choose(&mut self.rng).unwrap(),
+        }
+    }
+
+    fn words(&mut self, count: usize) -> Vec<&'static str> {
+        (0..count).map(|_| self.word()).collect()
+    }
+
+    /// One sentence of at most `max_words` words. The classic opening is
+    /// kept as a sentence of its own.
+    fn sentence(&mut self, max_words: usize) -> (String, usize) {
+        let len = if self.classic.is_empty() {
+            self.rng
+                .gen_range(MIN_SENTENCE_WORDS..=MAX_SENTENCE_WORDS)
+                .min(max_words)
+        } else {
+            self.classic.len().min(max_words)
+        };
+
+        let mut words: Vec<String> = self.words(len).into_iter().map(String::from).collect();
+        // Long sentences get a comma somewhere in the middle
+        if len > 7 && !words.iter().any(|w| w.ends_with(',')) {
+            let at = self.rng.gen_range(2..len - 3);
+            words[at].push(',');
+        }
+        let last = words.last_mut().unwrap();
+        if let Some(stripped) = last.strip_suffix(',') {
+            *last = stripped.to_string();
+        }
+
+        (format!("{}.", capitalize(&words.join(" "))), len)
+    }
+
+    fn paragraph(&mut self, words: usize) -> String {
+        let mut sentences = Vec::new();
+        let mut left = words;
+        while left > 0 {
+            let (sentence, len) = self.sentence(left);
+            sentences.push(sentence);
+            left -= len;
+        }
+        sentences.join(" ")
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn validate_count(name: &str, value: usize) -> Result<(), AppError> {
+    if (1..=MAX_COUNT).contains(&value) {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "{} must be between 1 and {}",
+            name, MAX_COUNT
+        )))
+    }
+}
+
+/// Placeholder text: `count` words, sentences or paragraphs (separated by a
+/// blank line). Paragraphs hold `words_per_paragraph` words (default 50).
+/// With `start_with_lorem` the text opens with "Lorem ipsum dolor sit amet,
+/// consectetur adipiscing elit".
+#[tauri::command]
+pub fn generate_lorem(
+    unit: LoremUnit,
+    count: usize,
+    words_per_paragraph: Option<usize>,
+    start_with_lorem: Option<bool>,
+) -> Result<String, AppError> {
+    validate_count("count", count)?;
+    let words_per_paragraph = words_per_paragraph.unwrap_or(DEFAULT_WORDS_PER_PARAGRAPH);
+    validate_count("words_per_paragraph", words_per_paragraph)?;
+
+    let mut generator = Generator {
+        rng: rand::thread_rng(),
+        classic: if start_with_lorem.unwrap_or(false) {
+            CLASSIC
+        } else {
+            &[]
+        },
+    };
+
+    Ok(match unit {
+        LoremUnit::Words => {
+            let classic = !generator.classic.is_empty();
+            let text = generator.words(count).join(" ");
+            let text = text.strip_suffix(',').unwrap_or(&text);
+            if classic {
+                capitalize(text)
+            } else {
+                text.to_string()
+            }
+        }
+        LoremUnit::Sentences => (0..count)
+            .map(|_| generator.sentence(MAX_SENTENCE_WORDS).0)
+            .collect::<Vec<_>>()
+            .join(" "),
+        LoremUnit::Paragraphs => (0..count)
+            .map(|_| generator.paragraph(words_per_paragraph))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    })
+}
@@ -0,0 +1,19 @@
+// Stateless developer utilities (formatters, encoders, generators). Each
+// submodule exposes its own Tauri commands.
+pub mod base64;
+pub mod case;
+pub mod color;
+pub mod cron;
+pub mod diff;
+pub mod hash;
+pub mod html;
+pub mod json;
+pub mod jwt;
+pub mod lorem;
+pub mod number_base;
+pub mod password;
+pub mod regex;
+pub mod slug;
+pub mod timestamp;
+pub mod url;
+pub mod uuid;
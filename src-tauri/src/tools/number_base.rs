@@ -0,0 +1,132 @@
+use crate::error::AppError;
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+fn radix_prefix(base: u32) -> Option<&'static str> {
+    match base {
+        2 => Some("0b"),
+        8 => Some("0o"),
+        16 => Some("0x"),
+        _ => None,
+    }
+}
+
+fn validate_base(name: &str, base: u32) -> Result<(), AppError> {
+    if (2..=36).contains(&base) {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "{} must be between 2 and 36, got {}",
+            name, base
+        )))
+    }
+}
+
+// Digits may be separated by `_` or spaces for readability, and a matching
+// `0x`/`0o`/`0b` prefix is accepted
+fn parse_magnitude(digits: &str, base: u32) -> Result<u128, AppError> {
+    let digits = radix_prefix(base)
+        .and_then(|p| {
+            digits
+                .get(..2)
+                .filter(|d| d.eq_ignore_ascii_case(p))
+                .map(|_| &digits[2..])
+        })
+        .unwrap_or(digits);
+
+    let mut value: u128 = 0;
+    let mut seen_digit = false;
+    for c in digits.chars() {
+        if c == '_' || c == ' ' {
+            continue;
+        }
+        let digit = c.to_digit(base).ok_or_else(|| {
+            AppError::Validation(format!("'{}' is not a valid base-{} digit", c, base))
+        })?;
+        value = value
+            .checked_mul(base as u128)
+            .and_then(|v| v.checked_add(digit as u128))
+            .ok_or_else(|| AppError::Validation("Number does not fit in 128 bits".into()))?;
+        seen_digit = true;
+    }
+
+    if !seen_digit {
+        return Err(AppError::Validation("No digits to convert".into()));
+    }
+    Ok(value)
+}
+
+fn format_magnitude(mut value: u128, base: u32) -> String {
+    if value == 0 {
+        return "0".into();
+    }
+
+    let mut out = Vec::new();
+    while value > 0 {
+        out.push(DIGITS[(value % base as u128) as usize]);
+        value /= base as u128;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap()
+}
+
+// Separates groups of `size` digits with `_`, counting from the right
+fn group_digits(digits: &str, size: usize) -> String {
+    let len = digits.len();
+    let mut out = String::with_capacity(len + len / size);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(size) {
+            out.push('_');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Converts an integer between bases 2 to 36 (up to 128 bits). `signed`
+/// allows a leading `-`; without it negative input is rejected. `prefix`
+/// adds `0x`/`0o`/`0b` for bases 16, 8 and 2. `group` separates every
+/// `group` digits with `_`. Output digits are lowercase.
+#[tauri::command]
+pub fn convert_number_base(
+    input: String,
+    from_base: u32,
+    to_base: u32,
+    signed: Option<bool>,
+    prefix: Option<bool>,
+    group: Option<usize>,
+) -> Result<String, AppError> {
+    validate_base("from_base", from_base)?;
+    validate_base("to_base", to_base)?;
+    if group == Some(0) {
+        return Err(AppError::Validation("group must be at least 1".into()));
+    }
+
+    let trimmed = input.trim();
+    let (negative, digits) = match trimmed.strip_prefix('-') {
+        Some(rest) if signed.unwrap_or(false) => (true, rest),
+        Some(_) => {
+            return Err(AppError::Validation(
+                "Negative numbers need signed mode".into(),
+            ))
+        }
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let value = parse_magnitude(digits, from_base)?;
+
+    let mut digits = format_magnitude(value, to_base);
+    if let Some(size) = group {
+        digits = group_digits(&digits, size);
+    }
+
+    let mut out = String::new();
+    if negative && value != 0 {
+        out.push('-');
+    }
+    if prefix.unwrap_or(false) {
+        out.push_str(radix_prefix(to_base).unwrap_or_default());
+    }
+    out.push_str(&digits);
+    Ok(out)
+}
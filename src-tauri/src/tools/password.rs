@@ -0,0 +1,108 @@
+use crate::error::AppError;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&()*+,-./:;<=>?@[]^_{|}~";
+// Characters that are easy to misread in many fonts
+const AMBIGUOUS: &str = "Il1|O0o";
+
+const MAX_LENGTH: usize = 1024;
+const MAX_COUNT: usize = 100;
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PasswordOptions {
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,
+}
+
+impl Default for PasswordOptions {
+    fn default() -> Self {
+        Self {
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+        }
+    }
+}
+
+impl PasswordOptions {
+    fn classes(&self) -> Vec<Vec<char>> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, chars)| {
+            chars
+                .chars()
+                .filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.contains(*c)))
+                .collect()
+        })
+        .collect()
+    }
+}
+
+// One character from every class first, the rest from all of them, then a
+// shuffle so the guaranteed ones don't sit at fixed positions
+fn generate_one(classes: &[Vec<char>], pool: &[char], length: usize) -> String {
+    let mut rng = OsRng;
+    let mut chars: Vec<char> = classes
+        .iter()
+        .map(|class| *class.choose(&mut rng).unwrap())
+        .collect();
+    chars.extend((chars.len()..length).map(|_| *pool.choose(&mut rng).unwrap()));
+    chars.shuffle(&mut rng);
+    chars.into_iter().collect()
+}
+
+/// Generates `count` (default 1) passwords from the OS random number
+/// generator. Every enabled character class appears at least once, so
+/// `length` must be at least the number of enabled classes. All classes
+/// are on when `options` is omitted.
+#[tauri::command]
+pub fn generate_password(
+    length: usize,
+    options: Option<PasswordOptions>,
+    count: Option<usize>,
+) -> Result<Vec<String>, AppError> {
+    let options = options.unwrap_or_default();
+    let count = count.unwrap_or(1);
+    if count == 0 || count > MAX_COUNT {
+        return Err(AppError::Validation(format!(
+            "Count must be between 1 and {}",
+            MAX_COUNT
+        )));
+    }
+
+    let classes = options.classes();
+    if classes.is_empty() {
+        return Err(AppError::Validation(
+            "Enable at least one character class".into(),
+        ));
+    }
+    if length < classes.len() || length > MAX_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Length must be between {} and {} for the selected character classes",
+            classes.len(),
+            MAX_LENGTH
+        )));
+    }
+
+    let pool: Vec<char> = classes.concat();
+    Ok((0..count)
+        .map(|_| generate_one(&classes, &pool, length))
+        .collect())
+}
@@ -0,0 +1,125 @@
+use crate::error::AppError;
+use regex::RegexBuilder;
+use serde::Serialize;
+
+// Stops runaway patterns like `a*` on large inputs from flooding the UI
+const MAX_MATCHES: usize = 10_000;
+
+#[derive(Serialize)]
+pub struct CaptureGroup {
+    pub index: usize,
+    pub name: Option<String>,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// A match with its byte offsets. `groups` excludes the whole match and
+/// skips groups that did not participate.
+#[derive(Serialize)]
+pub struct RegexMatch {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub groups: Vec<CaptureGroup>,
+}
+
+/// `truncated` is set when the text had more than `MAX_MATCHES` matches and
+/// only the first ones are returned.
+#[derive(Serialize)]
+pub struct RegexResult {
+    pub matches: Vec<RegexMatch>,
+    pub truncated: bool,
+}
+
+/// Runs `pattern` over `text`. `flags` uses the usual letters: `i` (case
+/// insensitive), `m` (multiline), `s` (dot matches newline), `x` (verbose).
+#[tauri::command]
+pub fn test_regex(
+    pattern: String,
+    text: String,
+    flags: Option<String>,
+) -> Result<RegexResult, AppError> {
+    let mut builder = RegexBuilder::new(&pattern);
+    for flag in flags.unwrap_or_default().chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            'x' => builder.ignore_whitespace(true),
+            other => {
+                return Err(AppError::Validation(format!(
+                    "Unknown regex flag '{}'",
+                    other
+                )))
+            }
+        };
+    }
+
+    let re = builder
+        .build()
+        .map_err(|e| AppError::Validation(format!("Invalid regex: {}", e)))?;
+    let names: Vec<Option<&str>> = re.capture_names().collect();
+
+    let mut captures = re.captures_iter(&text);
+    let matches: Vec<RegexMatch> = captures
+        .by_ref()
+        .take(MAX_MATCHES)
+        .map(|caps| {
+            let whole = caps.get(0).expect("group 0 always participates");
+            let groups = caps
+                .iter()
+                .enumerate()
+                .skip(1)
+                .filter_map(|(index, group)| {
+                    group.map(|g| CaptureGroup {
+                        index,
+                        name: names[index].map(str::to_string),
+                        start: g.start(),
+                        end: g.end(),
+                        text: g.as_str().to_string(),
+                    })
+                })
+                .collect();
+
+            RegexMatch {
+                start: whole.start(),
+                end: whole.end(),
+                text: whole.as_str().to_string(),
+                groups,
+            }
+        })
+        .collect();
+
+    Ok(RegexResult {
+        truncated: captures.next().is_some(),
+        matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_truncated_results() {
+        let text = "a".repeat(MAX_MATCHES + 1);
+        let result = test_regex("a".into(), text, None).unwrap();
+        assert_eq!(result.matches.len(), MAX_MATCHES);
+        assert!(result.truncated);
+
+        let result = test_regex("a".into(), "a".repeat(MAX_MATCHES), None).unwrap();
+        assert_eq!(result.matches.len(), MAX_MATCHES);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn reports_bad_patterns_and_flags_as_validation() {
+        for (pattern, flags) in [("(", None), ("a", Some("q".to_string()))] {
+            assert!(matches!(
+                test_regex(pattern.into(), "a".into(), flags),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+}
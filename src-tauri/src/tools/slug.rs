@@ -0,0 +1,170 @@
+use crate::error::AppError;
+use serde::Deserialize;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SlugOptions {
+    pub separator: String,
+    /// In characters; the cut never leaves a trailing separator
+    pub max_length: Option<usize>,
+    /// Map accented and other Latin letters to ASCII (`é` -> `e`,
+    /// `ß` -> `ss`). When false, non-ASCII characters are dropped.
+    pub transliterate: bool,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        Self {
+            separator: "-".into(),
+            max_length: None,
+            transliterate: true,
+        }
+    }
+}
+
+/// Lowercases and replaces every run of characters other than ASCII letters
+/// and digits with one `separator`. Leading and trailing separators are
+/// kept, matching the branch-name generator in the UI.
+pub(crate) fn collapse_to_separator(text: &str, separator: char) -> String {
+    let mut out = String::new();
+    let mut in_separator = false;
+    for c in text.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c);
+            in_separator = false;
+        } else if !in_separator {
+            out.push(separator);
+            in_separator = true;
+        }
+    }
+    out
+}
+
+// Letters that have no decomposition into an ASCII base
+fn transliterate_char(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'œ' => "oe",
+        'Œ' => "OE",
+        'ø' => "o",
+        'Ø' => "O",
+        'đ' | 'ð' => "d",
+        'Đ' | 'Ð' => "D",
+        'ł' => "l",
+        'Ł' => "L",
+        'þ' => "th",
+        'Þ' => "TH",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+// NFKD splits `é` into `e` plus a combining accent, which is then dropped.
+// Anything still non-ASCII (emoji, other scripts) becomes a separator.
+fn to_ascii(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.nfkd().filter(|c| !is_combining_mark(*c)) {
+        match transliterate_char(c) {
+            Some(ascii) => out.push_str(ascii),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+#[tauri::command]
+pub fn slugify(input: String, options: Option<SlugOptions>) -> Result<String, AppError> {
+    let options = options.unwrap_or_default();
+
+    let mut chars = options.separator.chars();
+    let separator = match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_punctuation() => c,
+        _ => {
+            return Err(AppError::Validation(
+                "Separator must be a single ASCII punctuation character".into(),
+            ))
+        }
+    };
+    if options.max_length == Some(0) {
+        return Err(AppError::Validation("max_length must be at least 1".into()));
+    }
+
+    let text = if options.transliterate {
+        to_ascii(&input)
+    } else {
+        input.chars().filter(char::is_ascii).collect()
+    };
+
+    let mut slug = collapse_to_separator(&text, separator)
+        .trim_matches(separator)
+        .to_string();
+    if let Some(max) = options.max_length {
+        // The slug is ASCII, so bytes and characters line up
+        slug.truncate(max);
+        slug = slug.trim_end_matches(separator).to_string();
+    }
+
+    if slug.is_empty() {
+        return Err(AppError::Validation(
+            "Input contains no letters or digits that fit in a slug".into(),
+        ));
+    }
+    Ok(slug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slug(input: &str) -> Result<String, AppError> {
+        slugify(input.into(), None)
+    }
+
+    #[test]
+    fn emoji_only_input_has_no_slug() {
+        for input in ["🚀", "🎉 ✨ 🔥", "👩‍💻"] {
+            assert!(
+                matches!(slug(input), Err(AppError::Validation(_))),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(slug("Ship it 🚀🚀 now").unwrap(), "ship-it-now");
+    }
+
+    #[test]
+    fn mixed_scripts_keep_the_latin_part() {
+        assert_eq!(slug("Hello 世界 World").unwrap(), "hello-world");
+        assert_eq!(slug("Привет мир release 2").unwrap(), "release-2");
+        assert_eq!(slug("東京Tower").unwrap(), "tower");
+        assert!(slug("Привет мир").is_err());
+    }
+
+    #[test]
+    fn strips_combining_diacritics() {
+        // Decomposed (e + U+0301) and precomposed forms give the same slug
+        assert_eq!(
+            slug("E\u{301}cole d\u{2019}e\u{301}te\u{301}").unwrap(),
+            "ecole-d-ete"
+        );
+        assert_eq!(slug("École d’été").unwrap(), "ecole-d-ete");
+        assert_eq!(slug("Straße Łódź").unwrap(), "strasse-lodz");
+    }
+
+    #[test]
+    fn collapses_repeated_separators() {
+        assert_eq!(slug("  a -- b__c...d  ").unwrap(), "a-b-c-d");
+        let options = SlugOptions {
+            separator: "_".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            slugify("--Hello,,  World!!".into(), Some(options)).unwrap(),
+            "hello_world"
+        );
+    }
+}
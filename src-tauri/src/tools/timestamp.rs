@@ -0,0 +1,86 @@
+use crate::error::AppError;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+
+// Epochs at or above this are treated as milliseconds; as seconds it would
+// be a date in the year 5138.
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+#[derive(Serialize)]
+pub struct ConvertedTimestamp {
+    pub unix_seconds: i64,
+    pub unix_millis: i64,
+    /// RFC 3339 in the requested offset (UTC by default)
+    pub iso: String,
+    pub human: String,
+}
+
+pub(crate) fn parse_input(input: &str) -> Result<DateTime<Utc>, AppError> {
+    if let Ok(epoch) = input.parse::<i64>() {
+        let parsed = if epoch.abs() >= MILLIS_THRESHOLD {
+            DateTime::from_timestamp_millis(epoch)
+        } else {
+            DateTime::from_timestamp(epoch, 0)
+        };
+        return parsed
+            .ok_or_else(|| AppError::Validation(format!("Timestamp {} is out of range", epoch)));
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    // ISO-8601 without an offset is taken as UTC
+    NaiveDateTime::parse_from_str(input, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S%.f"))
+        .map(|naive| naive.and_utc())
+        .map_err(|_| {
+            AppError::Validation(format!(
+                "'{}' is neither a unix timestamp nor an ISO-8601 date",
+                input
+            ))
+        })
+}
+
+/// Converts a unix epoch (seconds or milliseconds) or an ISO-8601 string
+/// into all representations. `offset_minutes` shifts `iso` and `human`
+/// away from UTC.
+#[tauri::command]
+pub fn convert_timestamp(
+    input: String,
+    offset_minutes: Option<i32>,
+) -> Result<ConvertedTimestamp, AppError> {
+    let utc = parse_input(input.trim())?;
+
+    let offset = offset_minutes
+        .unwrap_or(0)
+        .checked_mul(60)
+        .and_then(FixedOffset::east_opt)
+        .ok_or_else(|| AppError::Validation("Offset must be within ±24 hours".into()))?;
+    let local = offset.from_utc_datetime(&utc.naive_utc());
+
+    Ok(ConvertedTimestamp {
+        unix_seconds: utc.timestamp(),
+        unix_millis: utc.timestamp_millis(),
+        iso: local.to_rfc3339(),
+        human: local.format("%A, %B %-d, %Y %H:%M:%S (UTC%:z)").to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_out_of_range_offsets() {
+        for offset in [1440, -1440, i32::MAX, i32::MIN] {
+            let result = convert_timestamp("0".into(), Some(offset));
+            assert!(
+                matches!(result, Err(AppError::Validation(_))),
+                "offset {}",
+                offset
+            );
+        }
+        assert!(convert_timestamp("0".into(), Some(-1439)).is_ok());
+    }
+}
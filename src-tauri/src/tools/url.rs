@@ -0,0 +1,73 @@
+use crate::error::AppError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UrlMode {
+    /// A single value: every reserved character is escaped
+    #[default]
+    Component,
+    /// A whole `a=1&b=2` query string: keys and values are escaped but the
+    /// `&` and `=` separators are kept, and `+` decodes to a space
+    Query,
+}
+
+// `urlencoding::decode` passes malformed escapes such as `%zz` through
+// unchanged, so check them up front.
+fn validate_escapes(input: &str) -> Result<(), AppError> {
+    let bytes = input.as_bytes();
+    for (i, b) in bytes.iter().enumerate() {
+        if *b != b'%' {
+            continue;
+        }
+        let valid = bytes
+            .get(i + 1..i + 3)
+            .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        if !valid {
+            return Err(AppError::Validation(format!(
+                "Invalid percent-escape at position {}",
+                i
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn decode_component(input: &str) -> Result<String, AppError> {
+    validate_escapes(input)?;
+    urlencoding::decode(input)
+        .map(|s| s.into_owned())
+        .map_err(|e| AppError::Validation(format!("Decoded text is not valid UTF-8: {}", e)))
+}
+
+fn map_query_pairs(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<String, AppError>,
+) -> Result<String, AppError> {
+    let pairs = input
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => Ok(format!("{}={}", f(key)?, f(value)?)),
+            None => f(pair),
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+    Ok(pairs.join("&"))
+}
+
+#[tauri::command]
+pub fn url_encode(input: String, mode: Option<UrlMode>) -> Result<String, AppError> {
+    match mode.unwrap_or_default() {
+        UrlMode::Component => Ok(urlencoding::encode(&input).into_owned()),
+        UrlMode::Query => {
+            map_query_pairs(&input, |part| Ok(urlencoding::encode(part).into_owned()))
+        }
+    }
+}
+
+#[tauri::command]
+pub fn url_decode(input: String, mode: Option<UrlMode>) -> Result<String, AppError> {
+    match mode.unwrap_or_default() {
+        UrlMode::Component => decode_component(&input),
+        UrlMode::Query => map_query_pairs(&input, |part| decode_component(&part.replace('+', " "))),
+    }
+}
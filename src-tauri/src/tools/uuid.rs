@@ -0,0 +1,32 @@
+use crate::error::AppError;
+use uuid::Uuid;
+
+const MAX_COUNT: usize = 1000;
+
+/// Generates `count` (default 1) UUIDs of the given version. v7 values are
+/// time-ordered, and `Uuid::now_v7` keeps them increasing within a batch.
+#[tauri::command]
+pub fn generate_uuid(version: u8, count: Option<usize>) -> Result<Vec<String>, AppError> {
+    let count = count.unwrap_or(1);
+    if count == 0 || count > MAX_COUNT {
+        return Err(AppError::Validation(format!(
+            "Count must be between 1 and {}",
+            MAX_COUNT
+        )));
+    }
+
+    let generate: fn() -> Uuid = match version {
+        4 => Uuid::new_v4,
+        7 => Uuid::now_v7,
+        v => {
+            return Err(AppError::Validation(format!(
+                "Unsupported UUID version {}, expected 4 or 7",
+                v
+            )))
+        }
+    };
+
+    Ok((0..count)
+        .map(|_| generate().hyphenated().to_string())
+        .collect())
+}
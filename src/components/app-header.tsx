@@ -4,6 +4,7 @@ import { useLocation } from "react-router-dom";
 import { SidebarTrigger } from "@/components/ui/sidebar"; // <-- USE IT
 import { cn } from "@/lib/utils";
 import { ThemeToggle } from "@/components/theme-toggle";
+import { MaintenanceMenu } from "@/components/maintenance-menu";
 
 const TITLES: Record<string, string> = {
   "/task": "Task Generator",
@@ -34,6 +35,9 @@ export function AppHeader() {
       {/* Spacer */}
       <div className="flex-1" />
 
+      {/* Database maintenance */}
+      <MaintenanceMenu />
+
       {/* Theme Toggle */}
       <ThemeToggle />
     </header>
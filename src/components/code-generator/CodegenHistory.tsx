@@ -11,8 +11,9 @@ import {
   Download,
   Upload,
 } from "lucide-react";
-import { cn } from "@/lib/utils";
+import { cn, formatError } from "@/lib/utils";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { save, open } from "@tauri-apps/plugin-dialog";
 import { toast } from "sonner";
 import { HistoryPayload } from "@/components/code-generator/codegen-types";
@@ -71,7 +72,7 @@ export function CodegenHistory({
       }
     } catch (e) {
       console.error("Failed to load history", e);
-      toast("Failed to load history", { description: String(e) });
+      toast("Failed to load history", { description: formatError(e) });
     }
   };
 
@@ -90,7 +91,7 @@ export function CodegenHistory({
       toast("History entry deleted");
     } catch (e) {
       console.error("Failed to delete history entry", e);
-      toast("Failed to delete history entry", { description: String(e) });
+      toast("Failed to delete history entry", { description: formatError(e) });
     }
   };
 
@@ -116,7 +117,7 @@ export function CodegenHistory({
       });
     } catch (e) {
       console.error("Failed to export history", e);
-      toast("Failed to export history", { description: String(e) });
+      toast("Failed to export history", { description: formatError(e) });
     }
   };
 
@@ -136,19 +137,26 @@ export function CodegenHistory({
         return; // User cancelled
       }
 
-      const count = await invoke<number>("import_codegen_history", {
+      const { imported, skipped } = await invoke<{
+        imported: number;
+        skipped: number;
+      }>("import_codegen_history", {
         filePath: filePath,
+        dedupe: true,
       });
 
       toast("History imported successfully", {
-        description: `Imported ${count} entries`,
+        description:
+          skipped > 0
+            ? `Imported ${imported} entries, skipped ${skipped} duplicates`
+            : `Imported ${imported} entries`,
       });
 
       // Refresh the history list
       await refreshHistory();
     } catch (e) {
       console.error("Failed to import history", e);
-      toast("Failed to import history", { description: String(e) });
+      toast("Failed to import history", { description: formatError(e) });
     }
   };
 
@@ -158,6 +166,15 @@ export function CodegenHistory({
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [refreshToken]);
 
+  useEffect(() => {
+    // Keep in sync with saves, imports and deletes from anywhere in the app
+    const unlisten = listen("codegen-updated", () => void refreshHistory());
+    return () => {
+      unlisten.then((off) => off());
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
   const filtered = useMemo(() => {
     const q = search.trim().toLowerCase();
     if (!q) return entries;
@@ -0,0 +1,58 @@
+"use client";
+
+import { DatabaseZap, ShieldCheck } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "sonner";
+import { Button } from "@/components/ui/button";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuLabel,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+import { formatError } from "@/lib/utils";
+
+export function MaintenanceMenu() {
+  const checkIntegrity = async () => {
+    try {
+      const problems = await invoke<string[]>("check_database_integrity");
+      if (problems.length === 1 && problems[0] === "ok") {
+        toast("Database integrity check passed");
+      } else {
+        toast.error("Database integrity problems found", {
+          description: problems.join("\n"),
+        });
+      }
+    } catch (e) {
+      console.error("Failed to check database integrity", e);
+      toast("Failed to check database integrity", {
+        description: formatError(e),
+      });
+    }
+  };
+
+  return (
+    <DropdownMenu>
+      <DropdownMenuTrigger asChild>
+        <Button
+          variant="ghost"
+          size="icon"
+          className="size-9"
+          aria-label="Database maintenance"
+        >
+          <DatabaseZap className="size-4" />
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end">
+        <DropdownMenuLabel>Database</DropdownMenuLabel>
+        <DropdownMenuSeparator />
+        <DropdownMenuItem onSelect={checkIntegrity}>
+          <ShieldCheck className="size-4" />
+          Check integrity
+        </DropdownMenuItem>
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+}
@@ -19,3 +19,14 @@ export function sanitizeBarcodeInput(raw: string): string {
     .replace(/[^\x20-\x7E]/g, "") // keep only printable ASCII
     .trim(); // remove leading/trailing spaces
 }
+
+/**
+ * Extracts a readable message from a Tauri command error.
+ * Rust commands reject with `{ kind, message }` (see `AppError`).
+ */
+export function formatError(e: unknown): string {
+  if (e && typeof e === "object" && "message" in e) {
+    return String((e as { message: unknown }).message);
+  }
+  return String(e);
+}
@@ -5,6 +5,7 @@ import { useState } from "react";
 import { Tabs, TabsList, TabsTrigger, TabsContent } from "@/components/ui/tabs";
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "sonner";
+import { formatError } from "@/lib/utils";
 
 import {
   CodeType,
@@ -58,7 +59,7 @@ export default function CodeGenerator() {
       setHistoryRefreshToken((t) => t + 1);
     } catch (e) {
       console.error("Failed to save history", e);
-      toast("Failed to save history", { description: String(e) });
+      toast("Failed to save history", { description: formatError(e) });
     }
   };
 
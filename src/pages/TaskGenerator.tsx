@@ -15,9 +15,11 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Plus, Trash2, Download, Upload } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { save, open } from "@tauri-apps/plugin-dialog";
 import { toast } from "sonner";
 import { Task } from "@/lib/types/task";
+import { formatError } from "@/lib/utils";
 
 import {
   ResizablePanelGroup,
@@ -52,6 +54,12 @@ export default function TaskGenerator() {
 
   useEffect(() => {
     loadTasks();
+
+    // Keep in sync with changes made from other windows
+    const unlisten = listen("tasks-updated", () => loadTasks());
+    return () => {
+      unlisten.then((off) => off());
+    };
   }, []);
 
   const resetForm = () => {
@@ -137,7 +145,7 @@ export default function TaskGenerator() {
       });
     } catch (e) {
       console.error("Failed to export tasks", e);
-      toast("Failed to export tasks", { description: String(e) });
+      toast("Failed to export tasks", { description: formatError(e) });
     }
   };
 
@@ -157,19 +165,26 @@ export default function TaskGenerator() {
         return; // User cancelled
       }
 
-      const count = await invoke<number>("import_tasks", {
+      const { imported, skipped } = await invoke<{
+        imported: number;
+        skipped: number;
+      }>("import_tasks", {
         filePath: filePath,
+        dedupe: true,
       });
 
       toast("Tasks imported successfully", {
-        description: `Imported ${count} tasks`,
+        description:
+          skipped > 0
+            ? `Imported ${imported} tasks, skipped ${skipped} duplicates`
+            : `Imported ${imported} tasks`,
       });
 
       // Reload tasks from DB
       await loadTasks();
     } catch (e) {
       console.error("Failed to import tasks", e);
-      toast("Failed to import tasks", { description: String(e) });
+      toast("Failed to import tasks", { description: formatError(e) });
     }
   };
 